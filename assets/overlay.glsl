@@ -0,0 +1,30 @@
+#version 300 es
+precision mediump float;
+
+#if defined(VERTEX) // vertex shader
+
+in vec3 pos;
+in vec2 uv;
+
+out vec2 TexCoords;
+
+uniform mat4 projection;
+
+void main() {
+	TexCoords = uv;
+	gl_Position = projection * vec4(pos, 1.);
+}
+
+#else // fragment shader
+out vec4 FragColor;
+
+in vec2 TexCoords;
+
+uniform vec4 color;
+uniform sampler2D tex;
+
+void main() {
+	FragColor = texture(tex, TexCoords) * color;
+}
+
+#endif
@@ -6,10 +6,12 @@ precision mediump float;
 in vec3 pos;
 in vec3 normal;
 in vec2 uv;
+in vec4 color;
 
 out vec3 FragPos;
 out vec3 Normal;
 out vec2 TexCoords;
+out vec4 VertexColor;
 
 uniform mat4 model;
 uniform mat4 view;
@@ -19,6 +21,7 @@ void main() {
 	FragPos = vec3(model * vec4(pos, 1.));
 	Normal = mat3(transpose(inverse(model))) * normal;
 	TexCoords = uv;
+	VertexColor = color;
 
 	gl_Position = projection * view * vec4(FragPos, 1.);
 }
@@ -29,22 +32,32 @@ out vec4 FragColor;
 in vec3 Normal;
 in vec3 FragPos;
 in vec2 TexCoords;
+in vec4 VertexColor;
 
 uniform vec3 light_pos;
+uniform vec3 light_dir;
+uniform float is_directional;
 uniform vec3 view_pos;
 uniform vec3 light_color;
 uniform float ambient_intensity;
+uniform vec3 ambient_color;
 uniform vec3 object_color;
 uniform float normal_map_intensity;
 uniform float specular_strength;
 uniform float shininess;
+uniform float show_normals;
+uniform vec3 emission;
+uniform vec3 fade_color;
+uniform float fade;
+uniform float seam_blend_height;
 uniform sampler2D diffuse_tex;
 uniform sampler2D normal_tex;
 
-vec3 ambient_color = vec3(1.);
 vec3 normal_map_flat_color = vec3(.5, .5, 1.);
 float light_linear_term = -0.02;
 float light_quadratic_term = 0.12;
+// world height of the floor, half a cell below the walking camera
+float floor_level = -0.5;
 
 mat3 cotangent_frame(vec3 normal, vec3 pos, vec2 uv) {
 	vec3 dp1 = dFdx(pos);
@@ -67,15 +80,26 @@ void main() {
 	normal_sample = mix(normal_map_flat_color, normal_sample, normal_map_intensity);
 	mat3 tbn = cotangent_frame(Normal, FragPos, TexCoords);
 	vec3 norm = normalize(tbn * (normal_sample * 2. - 1.));
+	if (seam_blend_height > 0.) {
+		// walls turn towards the floor normal at their base, horizontal surfaces are left alone
+		float seam = 1. - smoothstep(floor_level, floor_level + seam_blend_height, FragPos.y);
+		norm = normalize(mix(norm, vec3(0., 1., 0.), seam * (1. - abs(normalize(Normal).y))));
+	}
+	if (show_normals > 0.5) {
+		// world space normal, for checking the normal map against the lit surface
+		FragColor = vec4(norm * .5 + .5, 1.);
+		return;
+	}
 
 	// diffuse
-	vec3 light_dir = normalize(light_pos - FragPos);
-	float diff = max(dot(norm, light_dir), 0.);
+	bool directional = is_directional > 0.5;
+	vec3 to_light = directional ? normalize(-light_dir) : normalize(light_pos - FragPos);
+	float diff = max(dot(norm, to_light), 0.);
 	vec3 diffuse = diff * light_color * (texture(diffuse_tex, TexCoords).rgb * object_color);
 
 	// specular
 	vec3 view_dir = normalize(view_pos - FragPos);
-	vec3 reflect_dir = reflect(-light_dir, norm);
+	vec3 reflect_dir = reflect(-to_light, norm);
 	float spec = pow(max(dot(view_dir, reflect_dir), 0.), shininess);
 	vec3 specular = specular_strength * spec * light_color;
 
@@ -84,11 +108,11 @@ void main() {
 
 	// light
 	float light_distance = length(light_pos - FragPos);
-	float light_attenuation = 1.0 / (1.0 + light_linear_term * light_distance +
+	float light_attenuation = directional ? 1.0 : 1.0 / (1.0 + light_linear_term * light_distance +
 		light_quadratic_term * (light_distance * light_distance));
 
-	vec3 result = (ambient + diffuse + specular) * light_attenuation;
-	FragColor = vec4(result, 1.);
+	vec3 result = (ambient + diffuse + specular) * light_attenuation + emission;
+	FragColor = vec4(mix(result, fade_color, fade), 1.) * VertexColor;
 }
 
 #endif
\ No newline at end of file
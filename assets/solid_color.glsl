@@ -0,0 +1,39 @@
+#version 300 es
+precision mediump float;
+
+#if defined(VERTEX) // vertex shader
+
+in vec3 pos;
+in vec4 color;
+
+out vec4 VertexColor;
+
+uniform mat4 model;
+uniform mat4 view;
+uniform mat4 projection;
+
+void main() {
+	VertexColor = color;
+	gl_Position = projection * view * model * vec4(pos, 1.);
+}
+
+#else // fragment shader
+out vec4 FragColor;
+
+in vec4 VertexColor;
+
+uniform vec3 object_color;
+uniform float alpha;
+
+void main() {
+	// screen-door transparency: drops pixels by a 2x2 ordered pattern, so translucent objects
+	// need no sorting or blending
+	vec2 cell = mod(floor(gl_FragCoord.xy), 2.);
+	if (alpha < (mod(cell.x * 2. + cell.y * 3., 4.) + 0.5) / 4.) {
+		discard;
+	}
+	// unlit, so markers stay visible in dark corridors
+	FragColor = vec4(object_color, 1.) * VertexColor;
+}
+
+#endif
@@ -0,0 +1,35 @@
+#version 300 es
+precision mediump float;
+
+#if defined(VERTEX) // vertex shader
+
+in vec3 pos;
+in vec2 uv;
+
+out vec2 TexCoords;
+
+uniform mat4 projection;
+
+void main() {
+	TexCoords = uv;
+	gl_Position = projection * vec4(pos, 1.);
+	// place on the near plane, in front of all scene geometry
+	gl_Position.z = -gl_Position.w;
+}
+
+#else // fragment shader
+out vec4 FragColor;
+
+in vec2 TexCoords;
+
+uniform vec3 color;
+uniform sampler2D font_tex;
+
+void main() {
+	if (texture(font_tex, TexCoords).a < .5) {
+		discard;
+	}
+	FragColor = vec4(color, 1.);
+}
+
+#endif
@@ -675,6 +675,9 @@ var importObject = {
         glTexParameteri: function (target, pname, param) {
             gl.texParameteri(target, pname, param);
         },
+        glGenerateMipmap: function (target) {
+            gl.generateMipmap(target);
+        },
         glUniform1fv: function (location, count, value) {
             GL.validateGLObjectID(GL.uniforms, location, 'glUniform1fv', 'location');
             assert((value & 3) == 0, 'Pointer to float data passed to glUniform1fv must be aligned to four bytes!');
@@ -1296,6 +1299,12 @@ var importObject = {
                 aniso = Math.min(aniso, max_supported);
                 gl.texParameterf(gl.TEXTURE_2D, anisotropy_extension.TEXTURE_MAX_ANISOTROPY_EXT, aniso);
             }
+        },
+        sapp_max_texture_anisotropy_level: function() {
+            if (anisotropy_extension) {
+                return gl.getParameter(anisotropy_extension.MAX_TEXTURE_MAX_ANISOTROPY_EXT);
+            }
+            return 0;
         }
     }
 };
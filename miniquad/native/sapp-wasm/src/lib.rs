@@ -358,6 +358,11 @@ extern "C" {
     pub fn sapp_is_fullscreen() -> bool;
     pub fn sapp_set_window_size(new_width: u32, new_height: u32);
     pub fn sapp_set_texture_anisotropy_level(aniso: f32);
+    pub fn sapp_max_texture_anisotropy_level() -> f32;
+}
+
+pub unsafe fn max_texture_aniso_level() -> f32 {
+    sapp_max_texture_anisotropy_level()
 }
 
 pub unsafe fn set_texture_aniso_level(aniso: f32) {
@@ -569,6 +569,7 @@ gl_loader!(
         dfactorAlpha: GLenum
     ) -> (),
     fn glTexParameteri(target: GLenum, pname: GLenum, param: GLint) -> (),
+    fn glGenerateMipmap(target: GLenum) -> (),
     fn glGetIntegerv(pname: GLenum, params: *mut GLint) -> (),
     fn glGetFloatv(pname: GLenum, params: *mut GLfloat) -> (),
     fn glEnable(cap: GLenum) -> (),
@@ -621,6 +621,10 @@ pub unsafe fn sapp_set_fullscreen(fullscreen: bool) {
     ShowWindow(_sapp_win32_hwnd, SW_SHOW);
 }
 
+pub unsafe fn max_texture_aniso_level() -> f32 {
+    _sapp.desc.max_aniso_supported
+}
+
 pub unsafe fn set_texture_aniso_level(aniso: f32) {
     let max = _sapp.desc.max_aniso_supported;
     if max > 0.0 {
@@ -80,6 +80,15 @@ pub struct Conf {
 
     /// Determines if the application user can resize the window
     pub window_resizable: bool,
+
+    /// Number of display refreshes to wait for before presenting a frame.
+    /// 1 syncs the frame rate to the display (vsync), 2 caps it at half the refresh rate, etc.
+    /// 0 falls back to the platform default, which is 1 on all platforms.
+    /// Honored on Windows (WGL), Linux (GLX), macOS and iOS, ignored on wasm, where the
+    /// browser paces frames, and on Android.
+    ///
+    /// Default: 0
+    pub swap_interval: i32,
 }
 
 impl Default for Conf {
@@ -93,6 +102,7 @@ impl Default for Conf {
             fullscreen: false,
             sample_count: 1,
             window_resizable: true,
+            swap_interval: 0,
         }
     }
 }
@@ -686,6 +686,19 @@ impl Context {
         unsafe { sapp_high_dpi() }
     }
 
+    /// Largest level accepted by `TextureParams::anisotropy`, 0 when anisotropic filtering is
+    /// not supported by the driver or not implemented on this platform
+    pub fn max_anisotropy(&self) -> f32 {
+        #[cfg(any(windows, target_arch = "wasm32"))]
+        {
+            unsafe { max_texture_aniso_level() }
+        }
+        #[cfg(not(any(windows, target_arch = "wasm32")))]
+        {
+            0.0
+        }
+    }
+
     pub fn apply_pipeline(&mut self, pipeline: &Pipeline) {
         self.cache.cur_pipeline = Some(*pipeline);
 
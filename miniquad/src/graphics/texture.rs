@@ -245,6 +245,21 @@ impl Texture {
         ctx.cache.restore_texture_binding(0);
     }
 
+    /// Generates the mip chain from the base level and switches to trilinear minification
+    pub fn generate_mipmaps(&self, ctx: &mut Context) {
+        ctx.cache.store_texture_binding(0);
+        ctx.cache.bind_texture(0, self.texture);
+        unsafe {
+            glGenerateMipmap(GL_TEXTURE_2D);
+            glTexParameteri(
+                GL_TEXTURE_2D,
+                GL_TEXTURE_MIN_FILTER,
+                GL_LINEAR_MIPMAP_LINEAR as i32,
+            );
+        }
+        ctx.cache.restore_texture_binding(0);
+    }
+
     pub fn resize(&mut self, ctx: &mut Context, width: u32, height: u32, bytes: Option<&[u8]>) {
         ctx.cache.store_texture_binding(0);
 
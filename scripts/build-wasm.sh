@@ -1,3 +1,4 @@
 #!/bin/bash
 cargo build --target wasm32-unknown-unknown --release &&
-cp ./target/wasm32-unknown-unknown/release/maze_walk.wasm ./pkg/maze_walk.wasm
\ No newline at end of file
+cp ./target/wasm32-unknown-unknown/release/maze_walk.wasm ./pkg/maze_walk.wasm &&
+cp "$(find ~/.cargo/registry/src -path "*/quad-snd-0.2*/js/audio.js" | sort | tail -n 1)" ./pkg/audio.js
\ No newline at end of file
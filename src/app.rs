@@ -10,6 +10,8 @@ pub enum CoreStage {
 	PostUpdate,
 	PreRender,
 	Render,
+	/// Screen-space drawing on top of the rendered scene
+	RenderOverlay,
 	Last,
 }
 
@@ -17,6 +19,7 @@ pub enum CoreStage {
 pub enum AppState {
 	Preload,
 	Play,
+	Complete,
 }
 
 impl Default for AppState {
@@ -38,6 +41,9 @@ struct AppSystem {
 	system: Box<dyn System<In = (), Out = ()>>,
 	stage: CoreStage,
 	typ: SystemType,
+	label: Option<&'static str>,
+	enabled: bool,
+	criteria: Option<Box<dyn Fn(&World) -> bool>>,
 }
 
 impl AppSystem {
@@ -50,7 +56,14 @@ impl AppSystem {
 		stage: CoreStage,
 		typ: SystemType,
 	) -> Self {
-		Self { system, stage, typ }
+		Self {
+			system,
+			stage,
+			typ,
+			label: None,
+			enabled: true,
+			criteria: None,
+		}
 	}
 
 	fn initialize(mut self, w: &mut World) -> Self {
@@ -92,9 +105,26 @@ impl App {
 		self.get_event::<T>().send(value)
 	}
 
+	/// Enables or disables all systems with the given label. Disabled systems are skipped until
+	/// enabled again. Returns false if no system has the label.
+	pub fn set_system_enabled(&mut self, label: &str, enabled: bool) -> bool {
+		let mut found = false;
+		for sys in self.systems.iter_mut().filter(|s| s.label == Some(label)) {
+			sys.enabled = enabled;
+			found = true;
+		}
+		found
+	}
+
 	fn run_systems(&mut self, predicate: impl Fn(SystemType) -> bool) {
 		for i in 0..self.systems.len() {
-			if (predicate)(self.systems[i].typ) {
+			let should_run = {
+				let sys = &self.systems[i];
+				sys.enabled
+					&& (predicate)(sys.typ)
+					&& sys.criteria.as_ref().map_or(true, |c| (c)(&self.world))
+			};
+			if should_run {
 				{
 					let sys = self.systems.get_mut(i).unwrap();
 					sys.system.run((), &mut self.world);
@@ -173,6 +203,16 @@ impl AppBuilder {
 		self
 	}
 
+	/// Adds a system that only runs when `criteria` returns true, evaluated before each run
+	pub fn add_system_with_criteria(
+		&mut self,
+		stage: CoreStage,
+		criteria: impl Fn(&World) -> bool + 'static,
+		system: impl System<In = (), Out = ()>,
+	) -> &mut Self {
+		self.add_system_to_stage(stage, system).run_if(criteria)
+	}
+
 	pub fn add_startup_system(&mut self, system: impl System<In = (), Out = ()>) -> &mut Self {
 		let s = AppSystem::new(system, CoreStage::First, SystemType::Startup);
 		self.systems.push(s);
@@ -233,6 +273,25 @@ impl AppBuilder {
 		self
 	}
 
+	/// Labels the most recently added system, so it can be toggled with `App::set_system_enabled`
+	pub fn label(&mut self, label: &'static str) -> &mut Self {
+		self.systems
+			.last_mut()
+			.expect("add a system before labeling it")
+			.label = Some(label);
+		self
+	}
+
+	/// Runs the most recently added system only when `criteria` returns true, in addition to its
+	/// stage and state conditions
+	pub fn run_if(&mut self, criteria: impl Fn(&World) -> bool + 'static) -> &mut Self {
+		self.systems
+			.last_mut()
+			.expect("add a system before setting its run criteria")
+			.criteria = Some(Box::new(criteria));
+		self
+	}
+
 	pub fn add_event<T>(&mut self) -> &mut Self
 	where
 		T: Component,
@@ -261,6 +320,24 @@ impl AppBuilder {
 		self
 	}
 
+	/// Panics if resource `T` hasn't been inserted yet, to catch plugins added in the wrong order.
+	/// `context` names the plugin or feature that depends on the resource.
+	pub fn require_resource<T: Component>(&mut self, context: &str) -> &mut Self {
+		if !self.world().contains_resource::<T>() {
+			panic!(
+				"{} requires resource {}, which must be inserted first. Check the plugin order.",
+				context,
+				std::any::type_name::<T>()
+			);
+		}
+		self
+	}
+
+	/// Panics if event `T` hasn't been added yet, see `require_resource`
+	pub fn require_event<T: Component>(&mut self, context: &str) -> &mut Self {
+		self.require_resource::<Events<T>>(context)
+	}
+
 	pub fn set_runner(&mut self, r: impl FnOnce(App) + 'static) -> &mut Self {
 		self.runner = Some(Box::new(r));
 		self
@@ -319,6 +396,10 @@ impl State {
 		self.current
 	}
 
+	pub fn get_pending(&self) -> Option<AppState> {
+		self.pending
+	}
+
 	pub fn schedule_transition(&mut self, new_state: AppState) {
 		self.pending = Some(new_state);
 	}
@@ -430,6 +511,78 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn disable_labeled_system() {
+		fn increment(mut c: ResMut<Count>) {
+			c.0 += 1;
+		}
+
+		let mut app = App::new()
+			.insert_resource(Count(0))
+			.add_system(increment.system())
+			.label("increment")
+			.build();
+
+		app.dispatch_update();
+		assert_eq!(count(&app), 1);
+		assert!(app.set_system_enabled("increment", false));
+		app.dispatch_update();
+		app.dispatch_update();
+		assert_eq!(count(&app), 1, "disabled");
+		assert!(app.set_system_enabled("increment", true));
+		app.dispatch_update();
+		assert_eq!(count(&app), 2, "enabled again");
+		assert!(!app.set_system_enabled("missing", false));
+	}
+
+	#[test]
+	fn run_criteria() {
+		struct Enabled(bool);
+		fn increment(mut c: ResMut<Count>) {
+			c.0 += 1;
+		}
+		fn set_enabled(app: &mut App, enabled: bool) {
+			app.get_resource::<Enabled>().0 = enabled;
+		}
+
+		let mut app = App::new()
+			.insert_resource(Count(0))
+			.insert_resource(Enabled(false))
+			.add_system_with_criteria(
+				CoreStage::Update,
+				|w| w.get_resource::<Enabled>().unwrap().0,
+				increment.system(),
+			)
+			.add_system_stateful(CoreStage::Update, AppState::Play, increment.system())
+			.run_if(|w| w.get_resource::<Enabled>().unwrap().0)
+			.build();
+
+		app.dispatch_update();
+		assert_eq!(count(&app), 0, "criteria not met");
+		set_enabled(&mut app, true);
+		app.dispatch_update();
+		assert_eq!(count(&app), 1, "stateful system in wrong state");
+		app.get_resource::<State>().schedule_transition(AppState::Play);
+		app.dispatch_update();
+		app.dispatch_update();
+		assert_eq!(count(&app), 4, "both criteria met");
+	}
+
+	#[test]
+	fn require_present_resource() {
+		App::new()
+			.insert_resource(Count(0))
+			.add_event::<Evt>()
+			.require_resource::<Count>("test")
+			.require_event::<Evt>("test");
+	}
+
+	#[test]
+	#[should_panic(expected = "test requires resource")]
+	fn require_missing_resource() {
+		App::new().require_resource::<Count>("test");
+	}
+
 	#[test]
 	fn state_transition() {
 		use super::State;
@@ -1,7 +1,7 @@
 use bevy_ecs_wasm::component::Component;
 use std::{
 	any::type_name,
-	collections::HashMap,
+	collections::{HashMap, HashSet},
 	marker::PhantomData,
 	mem::swap,
 	sync::{Arc, Mutex},
@@ -11,7 +11,7 @@ use crate::prelude::*;
 
 impl AppBuilder {
 	pub fn add_asset_type<T: Component>(&mut self) -> &mut Self {
-		self.add_asset_type_with_loader::<T, _>(MiniquadFileLoader {})
+		self.add_asset_type_with_loader::<T, _>(MiniquadFileLoader::default())
 	}
 
 	fn add_asset_type_with_loader<T: Component, FL: FileLoader>(
@@ -35,6 +35,18 @@ impl AppBuilder {
 			.use_processor(loader);
 		self
 	}
+
+	/// Lines starting with `directive` followed by a quoted path are replaced with the contents
+	/// of that file before the asset is processed. Paths are relative to the including file.
+	pub fn use_asset_includes<T: Component>(&mut self, directive: &'static str) -> &mut Self {
+		self.world
+			.as_mut()
+			.unwrap()
+			.get_resource_mut::<Assets<T>>()
+			.unwrap()
+			.include_directive = Some(directive);
+		self
+	}
 }
 
 #[derive(Debug, PartialEq)]
@@ -81,9 +93,22 @@ pub struct Assets<T: Component> {
 	values: HashMap<HandleId, T>,
 	last_id: HandleId,
 	pending_created_events: Vec<Handle<T>>,
+	pending_removed_events: Vec<Handle<T>>,
+	/// Ids of handles sent with asset events in the last two updates, which the events hold on
+	/// to until they're cleared
+	sent_events: [Vec<HandleId>; 2],
+	/// Handles found dropped in the last update but kept alive by asset events or loads, whose
+	/// assets are removed once those are gone
+	unreferenced: HashSet<HandleId>,
+	/// Number of distinct files loaded so far, including the ones still loading
+	requested_loads: usize,
 	loading_files: Arc<Mutex<Vec<PendingAsset<T>>>>,
 	processor: Option<Processor<T>>,
 	loader: Box<dyn FileLoader>,
+	include_directive: Option<&'static str>,
+	includes: Arc<Mutex<HashMap<String, IncludeFile>>>,
+	/// Handles of loading and loaded files, to avoid loading the same path twice
+	paths: Arc<Mutex<HashMap<String, HandleId>>>,
 }
 
 pub type Processor<T> = Box<dyn Fn(Vec<u8>) -> Result<T, String> + Send + Sync>;
@@ -94,6 +119,14 @@ struct PendingAsset<T> {
 	bytes: Option<Vec<u8>>,
 }
 
+enum IncludeFile {
+	Loading,
+	Loaded(String),
+	Failed(String),
+}
+
+const MAX_INCLUDE_DEPTH: usize = 16;
+
 impl<T: Component> Assets<T> {
 	fn new(loader: impl FileLoader) -> Self {
 		Self {
@@ -101,9 +134,16 @@ impl<T: Component> Assets<T> {
 			values: HashMap::new(),
 			last_id: 0,
 			pending_created_events: vec![],
+			pending_removed_events: vec![],
+			sent_events: Default::default(),
+			unreferenced: HashSet::new(),
+			requested_loads: 0,
 			loading_files: Default::default(),
 			processor: None,
 			loader: Box::new(loader),
+			include_directive: None,
+			includes: Default::default(),
+			paths: Default::default(),
 		}
 	}
 
@@ -117,32 +157,98 @@ impl<T: Component> Assets<T> {
 		self.values.get(&handle.id())
 	}
 
+	/// Changes an asset in place. It's sent again with `AssetEvent::Added` like a reloaded file,
+	/// once per update however often it's changed.
+	pub fn get_mut(&mut self, handle: &Handle<T>) -> Option<&mut T> {
+		let value = self.values.get_mut(&handle.id())?;
+		if !self.pending_created_events.contains(handle) {
+			self.pending_created_events.push(handle.clone());
+		}
+		Some(value)
+	}
+
+	/// Unloads an asset without waiting for its handles to be dropped, and sends
+	/// `AssetEvent::Removed` for it with the other asset events. Handles still held elsewhere
+	/// resolve to `None` from `get` afterwards.
+	pub fn remove(&mut self, handle: &Handle<T>) -> Option<T> {
+		let value = self.values.remove(&handle.id());
+		self.handles.retain(|h| h != handle);
+		self.pending_created_events.retain(|h| h != handle);
+		self.loading_files
+			.lock()
+			.unwrap()
+			.retain(|f| f.handle != *handle);
+		self.paths
+			.lock()
+			.unwrap()
+			.retain(|_, id| *id != handle.id());
+		if value.is_some() {
+			self.pending_removed_events.push(handle.clone());
+		}
+		value
+	}
+
+	/// Loaded assets with their handles. Assets whose handles have all been dropped are skipped
+	/// from the next update on, even while asset events still keep them from being removed.
+	pub fn iter(&self) -> impl Iterator<Item = (&Handle<T>, &T)> {
+		self.handles
+			.iter()
+			.filter(move |h| !self.unreferenced.contains(&h.id()))
+			.filter_map(move |h| self.values.get(&h.id()).map(|v| (h, v)))
+	}
+
 	pub fn load(&mut self, path: &str) -> Handle<T> {
+		let existing_id = self.paths.lock().unwrap().get(path).copied();
+		let existing = existing_id.and_then(|id| self.handles.iter().find(|h| h.id() == id));
+		if let Some(existing) = existing {
+			return existing.clone();
+		}
 		let handle = self.create_handle();
+		self.requested_loads += 1;
+		self.paths
+			.lock()
+			.unwrap()
+			.insert(path.to_string(), handle.id());
 		let handle_clone = handle.clone();
 		let handle_id = handle.id();
+		let weak_id = Arc::downgrade(&handle.id);
 		let path_string = path.to_string();
 		self.loading_files.lock().unwrap().push(PendingAsset {
 			handle: handle_clone,
-			path: path_string,
+			path: path_string.clone(),
 			bytes: None,
 		});
 		let files = Arc::clone(&self.loading_files);
+		let paths = Arc::clone(&self.paths);
 		self.loader.load(
 			path,
 			Box::new(move |result| {
 				let mut files_guard = files.lock().unwrap();
 				let file_index = files_guard
 					.iter()
-					.position(|f| f.handle.id() == handle_id)
-					.expect("unknown loaded asset");
-				let file = &mut files_guard[file_index];
-				match result {
-					Ok(bytes) => file.bytes = Some(bytes),
-					Err(e) => {
+					.position(|f| f.handle.id() == handle_id);
+				match (result, file_index) {
+					(Ok(bytes), Some(file_index)) => files_guard[file_index].bytes = Some(bytes),
+					(Ok(bytes), None) => {
+						// file changed on disk after loading, process it again if still in use
+						if let Some(id) = weak_id.upgrade() {
+							files_guard.push(PendingAsset {
+								handle: Handle {
+									id,
+									_p: PhantomData,
+								},
+								path: path_string.clone(),
+								bytes: Some(bytes),
+							});
+						}
+					}
+					(Err(e), Some(file_index)) => {
 						let file = files_guard.remove(file_index);
+						// allow retrying the load later
+						paths.lock().unwrap().remove(&file.path);
 						error!("Failed to load {}: {}", file.path, e)
 					}
+					(Err(e), None) => error!("Failed to reload {}: {}", path_string, e),
 				}
 			}),
 		);
@@ -153,6 +259,12 @@ impl<T: Component> Assets<T> {
 		self.loading_files.lock().unwrap().is_empty()
 	}
 
+	/// Number of finished and requested file loads. Failed loads count as finished.
+	pub fn load_progress(&self) -> (usize, usize) {
+		let loading = self.loading_files.lock().unwrap().len();
+		(self.requested_loads.saturating_sub(loading), self.requested_loads)
+	}
+
 	fn create_handle(&mut self) -> Handle<T> {
 		self.last_id += 1;
 		let id = self.last_id;
@@ -172,21 +284,198 @@ impl<T: Component> Assets<T> {
 	) {
 		self.processor = Some(Box::new(loader));
 	}
+
+	/// Returns the file contents with includes substituted, or None if some of the included files
+	/// are still loading.
+	fn resolve_includes(
+		&mut self,
+		directive: &str,
+		path: &str,
+		bytes: &[u8],
+	) -> Result<Option<Vec<u8>>, String> {
+		let source = std::str::from_utf8(bytes).map_err(|_| "failed to read utf8".to_string())?;
+		let mut missing = vec![];
+		let resolved = {
+			let includes = self.includes.lock().unwrap();
+			expand_includes(directive, path, source, &includes, &mut missing, 0)?
+		};
+		for include_path in missing.into_iter() {
+			self.load_include(include_path);
+		}
+		Ok(resolved.map(String::into_bytes))
+	}
+
+	fn load_include(&mut self, path: String) {
+		self.includes
+			.lock()
+			.unwrap()
+			.insert(path.clone(), IncludeFile::Loading);
+		let includes = Arc::clone(&self.includes);
+		let include_path = path.clone();
+		self.loader.load(
+			&path,
+			Box::new(move |result| {
+				let file = match result {
+					Ok(bytes) => IncludeFile::Loaded(String::from_utf8_lossy(&bytes).into_owned()),
+					Err(e) => IncludeFile::Failed(e),
+				};
+				includes.lock().unwrap().insert(include_path.clone(), file);
+			}),
+		);
+	}
+}
+
+fn expand_includes(
+	directive: &str,
+	path: &str,
+	source: &str,
+	includes: &HashMap<String, IncludeFile>,
+	missing: &mut Vec<String>,
+	depth: usize,
+) -> Result<Option<String>, String> {
+	if depth > MAX_INCLUDE_DEPTH {
+		return Err(format!(
+			"include depth limit of {} exceeded in {}, check for recursive includes",
+			MAX_INCLUDE_DEPTH, path
+		));
+	}
+	let mut expanded = String::with_capacity(source.len());
+	let mut complete = true;
+	for line in source.lines() {
+		let argument = match line.trim().strip_prefix(directive) {
+			Some(a) => a.trim(),
+			None => {
+				expanded.push_str(line);
+				expanded.push('\n');
+				continue;
+			}
+		};
+		let include_path = match argument.strip_prefix('"').and_then(|a| a.strip_suffix('"')) {
+			Some(p) => relative_path(path, p),
+			None => return Err(format!("malformed include in {}: {}", path, line.trim())),
+		};
+		match includes.get(&include_path) {
+			Some(IncludeFile::Loaded(contents)) => {
+				let nested = expand_includes(
+					directive,
+					&include_path,
+					contents,
+					includes,
+					missing,
+					depth + 1,
+				)?;
+				match nested {
+					Some(contents) => expanded.push_str(&contents),
+					None => complete = false,
+				}
+			}
+			Some(IncludeFile::Loading) => complete = false,
+			Some(IncludeFile::Failed(e)) => {
+				return Err(format!("failed to load include {}: {}", include_path, e))
+			}
+			None => {
+				if !missing.contains(&include_path) {
+					missing.push(include_path);
+				}
+				complete = false;
+			}
+		}
+	}
+	Ok(if complete { Some(expanded) } else { None })
+}
+
+fn relative_path(from_file: &str, path: &str) -> String {
+	match from_file.rfind('/') {
+		Some(separator_pos) => format!("{}/{}", &from_file[..separator_pos], path),
+		None => path.to_string(),
+	}
 }
 
 trait FileLoader: Send + Sync + 'static {
+	/// Loads the file at `path`. The callback may be invoked again if the file changes later.
 	fn load(&mut self, path: &str, callback: LoaderCallback);
+	/// Called every frame, to check loaded files for changes
+	fn poll_changes(&mut self) {}
 }
 type LoaderCallback = Box<dyn Fn(Result<Vec<u8>, String>) + Send + Sync + 'static>;
 
-struct MiniquadFileLoader;
+#[derive(Default)]
+struct MiniquadFileLoader {
+	#[cfg(not(target_arch = "wasm32"))]
+	watcher: FileWatcher,
+}
 impl FileLoader for MiniquadFileLoader {
+	#[cfg(target_arch = "wasm32")]
+	fn load(&mut self, path: &str, callback: LoaderCallback) {
+		miniquad::fs::load_file(path, move |res| callback(res.map_err(|e| e.to_string())))
+	}
+
+	#[cfg(not(target_arch = "wasm32"))]
 	fn load(&mut self, path: &str, callback: LoaderCallback) {
+		let callback = Arc::new(callback);
+		self.watcher.watch(path, Arc::clone(&callback));
 		miniquad::fs::load_file(path, move |res| callback(res.map_err(|e| e.to_string())))
 	}
+
+	#[cfg(not(target_arch = "wasm32"))]
+	fn poll_changes(&mut self) {
+		self.watcher.poll();
+	}
+}
+
+/// Reloads files when their modification time changes
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Default)]
+struct FileWatcher {
+	files: Vec<WatchedFile>,
+	last_poll: Option<std::time::Instant>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+struct WatchedFile {
+	path: String,
+	modified: Option<std::time::SystemTime>,
+	callback: Arc<LoaderCallback>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileWatcher {
+	const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+	fn watch(&mut self, path: &str, callback: Arc<LoaderCallback>) {
+		self.files.push(WatchedFile {
+			path: path.to_string(),
+			modified: Self::modified_time(path),
+			callback,
+		});
+	}
+
+	fn poll(&mut self) {
+		let now = std::time::Instant::now();
+		if matches!(self.last_poll, Some(last) if now - last < Self::POLL_INTERVAL) {
+			return;
+		}
+		self.last_poll = Some(now);
+		for file in self.files.iter_mut() {
+			let modified = Self::modified_time(&file.path);
+			if modified.is_some() && modified != file.modified {
+				file.modified = modified;
+				info!("Reloading {}", file.path);
+				let callback = Arc::clone(&file.callback);
+				miniquad::fs::load_file(&file.path, move |res| {
+					callback(res.map_err(|e| e.to_string()))
+				});
+			}
+		}
+	}
+
+	fn modified_time(path: &str) -> Option<std::time::SystemTime> {
+		std::fs::metadata(path).and_then(|m| m.modified()).ok()
+	}
 }
 
 fn update_assets<T: Component>(mut assets: ResMut<Assets<T>>, mut evt: EventWriter<AssetEvent<T>>) {
+	assets.loader.poll_changes();
 	let loaded_files: Option<Vec<PendingAsset<T>>> = {
 		let mut files = assets.loading_files.lock().unwrap();
 		let mut loaded_files = Option::<Vec<PendingAsset<T>>>::None;
@@ -201,15 +490,40 @@ fn update_assets<T: Component>(mut assets: ResMut<Assets<T>>, mut evt: EventWrit
 
 	if let Some(files) = loaded_files {
 		for file in files.into_iter() {
+			let PendingAsset {
+				handle,
+				path,
+				bytes,
+			} = file;
+			let bytes = bytes.unwrap();
+			let bytes = if let Some(directive) = assets.include_directive {
+				match assets.resolve_includes(directive, &path, &bytes) {
+					Ok(Some(resolved)) => resolved,
+					Ok(None) => {
+						// wait for included files to load
+						assets.loading_files.lock().unwrap().push(PendingAsset {
+							handle,
+							path,
+							bytes: Some(bytes),
+						});
+						continue;
+					}
+					Err(e) => {
+						assets.paths.lock().unwrap().remove(&path);
+						error!("Failed to resolve includes in {}: {}", path, e);
+						continue;
+					}
+				}
+			} else {
+				bytes
+			};
 			if let Some(processor) = &assets.processor {
-				let PendingAsset {
-					handle,
-					path,
-					bytes,
-				} = file;
-				match (processor)(bytes.unwrap()) {
+				match (processor)(bytes) {
 					Ok(value) => assets.insert_asset(&handle, value),
-					Err(e) => error!("Failed to process file {}: {}", path, e),
+					Err(e) => {
+						assets.paths.lock().unwrap().remove(&path);
+						error!("Failed to process file {}: {}", path, e)
+					}
 				}
 			} else {
 				error!("No processor for asset type {:?}", type_name::<T>())
@@ -217,9 +531,18 @@ fn update_assets<T: Component>(mut assets: ResMut<Assets<T>>, mut evt: EventWrit
 		}
 	}
 
+	let assets = &mut *assets;
+	// events sent two updates ago were cleared at the start of this frame
+	assets.sent_events.swap(0, 1);
+	assets.sent_events[1].clear();
 	for handle in assets.pending_created_events.drain(..) {
+		assets.sent_events[1].push(handle.id());
 		evt.send(AssetEvent::Added(handle));
 	}
+	for handle in assets.pending_removed_events.drain(..) {
+		assets.sent_events[1].push(handle.id());
+		evt.send(AssetEvent::Removed(handle));
+	}
 	let dropped = {
 		let mut dropped = Option::<Vec<Handle<T>>>::None;
 		let mut kept_handles = vec![];
@@ -237,9 +560,28 @@ fn update_assets<T: Component>(mut assets: ResMut<Assets<T>>, mut evt: EventWrit
 		swap(&mut assets.handles, &mut kept_handles);
 		dropped
 	};
+	assets.unreferenced = {
+		let loading_files = assets.loading_files.lock().unwrap();
+		let sent_events = &assets.sent_events;
+		assets
+			.handles
+			.iter()
+			.filter(|handle| {
+				let events = sent_events.iter().flatten().filter(|id| **id == handle.id());
+				let loading = loading_files.iter().filter(|f| f.handle == **handle);
+				Arc::strong_count(&handle.id) <= 1 + events.count() + loading.count()
+			})
+			.map(|handle| handle.id())
+			.collect()
+	};
 	if let Some(handles) = dropped {
 		for handle in handles.into_iter() {
 			assets.values.remove(&handle.id);
+			assets
+				.paths
+				.lock()
+				.unwrap()
+				.retain(|_, id| *id != handle.id());
 			evt.send(AssetEvent::Removed(handle));
 		}
 	}
@@ -306,6 +648,83 @@ mod tests {
 		assert_eq!(read(app), &[1, 2, 3, -2, -3], "frame 2");
 	}
 
+	#[test]
+	fn remove_asset_while_referenced() {
+		fn log_removed(mut evt: EventReader<AssetEvent<i32>>, mut events: ResMut<IntEvents>) {
+			for e in evt.iter() {
+				if let AssetEvent::Removed(h) = e {
+					events.0.push(h.id() as i32);
+				}
+			}
+		}
+
+		let app = &mut App::new()
+			.add_asset_type::<i32>()
+			.insert_resource(IntEvents::default())
+			.add_system_to_stage(CoreStage::AssetEvents, log_removed.system())
+			.build();
+		let one = assets_i(app).add(1);
+		app.dispatch_update();
+		assert_eq!(assets_i(app).remove(&one), Some(1));
+		assert_eq!(assets_i(app).get(&one), None);
+		assert_eq!(assets_i(app).remove(&one), None);
+		app.dispatch_update();
+		assert_eq!(read(app), &[one.id() as i32]);
+		// dropping the last handle doesn't remove it again
+		drop(one);
+		app.dispatch_update();
+		app.dispatch_update();
+		assert_eq!(read(app).len(), 1);
+	}
+
+	#[test]
+	fn modify_asset() {
+		fn log_added(mut evt: EventReader<AssetEvent<i32>>, mut events: ResMut<IntEvents>) {
+			for e in evt.iter() {
+				if let AssetEvent::Added(h) = e {
+					events.0.push(h.id() as i32);
+				}
+			}
+		}
+
+		let app = &mut App::new()
+			.add_asset_type::<i32>()
+			.insert_resource(IntEvents::default())
+			.add_system_to_stage(CoreStage::AssetEvents, log_added.system())
+			.build();
+		let one = assets_i(app).add(1);
+		app.dispatch_update();
+		*assets_i(app).get_mut(&one).unwrap() = 2;
+		*assets_i(app).get_mut(&one).unwrap() += 1;
+		app.dispatch_update();
+		assert_eq!(assets_i(app).get(&one), Some(&3));
+		assert_eq!(read(app), &[one.id() as i32, one.id() as i32]);
+	}
+
+	#[test]
+	fn iterate_referenced_assets() {
+		let app = &mut App::new().add_asset_type::<i32>().build();
+		let one = assets_i(app).add(1);
+		let two = assets_i(app).add(2);
+		let values = |app: &mut App| {
+			let mut values = assets_i(app).iter().map(|(_, v)| *v).collect::<Vec<_>>();
+			values.sort_unstable();
+			values
+		};
+		assert_eq!(values(app), &[1, 2]);
+		app.dispatch_update();
+		assert_eq!(values(app), &[1, 2]);
+		// skipped from the next update, while its Added event still keeps it from being removed
+		drop(two);
+		app.dispatch_update();
+		assert_eq!(values(app), &[1]);
+		assert_eq!(assets_i(app).handles.len(), 2);
+		assert!(assets_i(app).iter().all(|(h, _)| *h == one));
+		app.dispatch_update();
+		assert_eq!(assets_i(app).handles.len(), 1);
+		assert_eq!(values(app), &[1]);
+	}
+
 	#[test]
 	fn file_loading() {
 		struct TestLoader;
@@ -364,17 +783,99 @@ mod tests {
 		assert_loaded(app, false, "1");
 		app.dispatch_update();
 		assert_loaded(app, false, "2");
+		assert_eq!(assets_s(app).load_progress(), (0, 2));
 		
 		// invoke loaded callbacks
 		(callbacks.lock().unwrap()[0])(Ok(vec![]));
 		(callbacks.lock().unwrap()[1])(Err(String::new()));
 
 		assert_loaded(app, false, "3");
+		assert_eq!(assets_s(app).load_progress(), (1, 2));
 
 		app.dispatch_update();
 
 		assert_loaded(app, true, "4");
+		assert_eq!(assets_s(app).load_progress(), (2, 2));
 		assert!(assets_s(app).get(&handle_one).is_some());
 		assert!(assets_s(app).get(&handle_two).is_none());
 	}
+
+	#[test]
+	fn deduplicate_loads() {
+		use super::AssetEvent::*;
+		fn count_added(mut evt: EventReader<AssetEvent<String>>, mut events: ResMut<IntEvents>) {
+			for e in evt.iter() {
+				if let Added(h) = e {
+					events.0.push(h.id() as i32);
+				}
+			}
+		}
+
+		let load_count: Arc<Mutex<Vec<String>>> = Default::default();
+		struct TestLoader(Arc<Mutex<Vec<String>>>);
+		impl FileLoader for TestLoader {
+			fn load(&mut self, path: &str, callback: LoaderCallback) {
+				self.0.lock().unwrap().push(path.to_string());
+				callback(match path {
+					"file" => Ok(vec![]),
+					_ => Err("not found".into()),
+				});
+			}
+		}
+
+		let app = &mut App::new()
+			.add_asset_type_with_loader::<String, _>(TestLoader(Arc::clone(&load_count)))
+			.use_asset_processor(|_| Ok(String::new()))
+			.insert_resource(IntEvents::default())
+			.add_system_to_stage(CoreStage::AssetEvents, count_added.system())
+			.build();
+		let first = assets_s(app).load("file");
+		let second = assets_s(app).load("file");
+		app.dispatch_update();
+		let third = assets_s(app).load("file");
+		assert_eq!(first.id(), second.id());
+		assert_eq!(first.id(), third.id());
+		app.dispatch_update();
+		assert_eq!(read(app), &[first.id() as i32]);
+
+		// failed loads are evicted, so they can be retried
+		let missing = assets_s(app).load("missing");
+		let missing_retry = assets_s(app).load("missing");
+		assert_ne!(missing.id(), missing_retry.id());
+		assert_eq!(*load_count.lock().unwrap(), &["file", "missing", "missing"]);
+	}
+
+	#[test]
+	fn include_resolution() {
+		struct TestLoader;
+		impl FileLoader for TestLoader {
+			fn load(&mut self, path: &str, callback: LoaderCallback) {
+				callback(match path {
+					"dir/main" => Ok("main\n#include \"part\"".into()),
+					"dir/part" => Ok("part\n  #include \"sub/leaf\"".into()),
+					"dir/sub/leaf" => Ok("leaf".into()),
+					"dir/recursive" => Ok("#include \"recursive\"".into()),
+					"dir/broken" => Ok("#include \"missing\"".into()),
+					_ => Err("not found".into()),
+				});
+			}
+		}
+
+		let app = &mut App::new()
+			.add_asset_type_with_loader::<String, _>(TestLoader {})
+			.use_asset_processor(|b| Ok(String::from_utf8(b).unwrap()))
+			.use_asset_includes::<String>("#include")
+			.build();
+		let main = assets_s(app).load("dir/main");
+		let recursive = assets_s(app).load("dir/recursive");
+		let broken = assets_s(app).load("dir/broken");
+		for _ in 0..5 {
+			app.dispatch_update();
+		}
+
+		assert!(assets_s(app).everything_loaded());
+		assert_eq!(assets_s(app).get(&main).unwrap(), "main\npart\nleaf\n");
+		assert!(assets_s(app).get(&recursive).is_none());
+		assert!(assets_s(app).get(&broken).is_none());
+	}
 }
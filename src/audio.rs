@@ -0,0 +1,141 @@
+use crate::prelude::*;
+use quad_snd::{AudioContext, PlaySoundParams};
+use std::collections::HashMap;
+
+pub fn plugin(app: &mut AppBuilder) {
+	app.require_event::<MouseButtonDown>("audio::plugin")
+		.add_asset_type::<Sound>()
+		.use_asset_processor(process_sound)
+		.insert_resource(Audio::default())
+		.add_system_to_stage(CoreStage::AssetEvents, release_changed_sounds.system())
+		.add_system_to_stage(CoreStage::Last, init_audio_context.system())
+		.add_system_to_stage(CoreStage::Last, play_queued_sounds.system());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SoundFormat {
+	Wav,
+	Ogg,
+}
+
+/// Encoded sound data, decoded by the audio backend on first playback
+pub struct Sound {
+	pub format: SoundFormat,
+	bytes: Vec<u8>,
+}
+
+pub fn process_wav(bytes: Vec<u8>) -> Result<Sound, String> {
+	if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+		return Err("not a WAV file".into());
+	}
+	Ok(Sound {
+		format: SoundFormat::Wav,
+		bytes,
+	})
+}
+
+pub fn process_ogg(bytes: Vec<u8>) -> Result<Sound, String> {
+	if !bytes.starts_with(b"OggS") {
+		return Err("not an Ogg file".into());
+	}
+	Ok(Sound {
+		format: SoundFormat::Ogg,
+		bytes,
+	})
+}
+
+/// Picks the WAV or Ogg processor based on the file signature
+pub fn process_sound(bytes: Vec<u8>) -> Result<Sound, String> {
+	if bytes.starts_with(b"OggS") {
+		process_ogg(bytes)
+	} else {
+		process_wav(bytes)
+	}
+}
+
+/// Playback queue. Browsers only allow audio to start from a user gesture, so on wasm the
+/// backend is initialized on the first key press or click and sounds played before that are
+/// dropped.
+#[derive(Default)]
+pub struct Audio {
+	context: Option<AudioContext>,
+	decoded: HashMap<HandleId, quad_snd::Sound>,
+	queue: Vec<Handle<Sound>>,
+}
+
+impl Audio {
+	pub fn play_sound(&mut self, sound: &Handle<Sound>) {
+		if self.context.is_some() {
+			self.queue.push(sound.clone());
+		}
+	}
+}
+
+fn init_audio_context(
+	mut audio: ResMut<Audio>,
+	input: Res<Keyboard>,
+	mut clicks: EventReader<MouseButtonDown>,
+) {
+	let clicked = clicks.iter().count() > 0;
+	if audio.context.is_none() {
+		let gesture = input.get_just_pressed().len() > 0 || clicked;
+		if cfg!(not(target_arch = "wasm32")) || gesture {
+			audio.context = Some(AudioContext::new());
+		}
+	}
+}
+
+fn release_changed_sounds(mut audio: ResMut<Audio>, mut events: EventReader<AssetEvent<Sound>>) {
+	let audio = &mut *audio;
+	for evt in events.iter() {
+		// reloaded sounds are decoded again on next playback
+		let handle = match evt {
+			AssetEvent::Added(handle) | AssetEvent::Removed(handle) => handle,
+		};
+		if let (Some(sound), Some(context)) = (audio.decoded.remove(&handle.id()), &audio.context) {
+			sound.delete(context);
+		}
+	}
+}
+
+fn play_queued_sounds(mut audio: ResMut<Audio>, sounds: Res<Assets<Sound>>) {
+	let Audio {
+		context,
+		decoded,
+		queue,
+	} = &mut *audio;
+	let context = match context {
+		Some(context) => context,
+		None => return,
+	};
+	for handle in queue.drain(..) {
+		let sound = match sounds.get(&handle) {
+			Some(sound) => sound,
+			None => continue,
+		};
+		decoded
+			.entry(handle.id())
+			.or_insert_with(|| quad_snd::Sound::load(context, &sound.bytes))
+			.play(
+				context,
+				PlaySoundParams {
+					looped: false,
+					volume: 1.0,
+				},
+			);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn detect_sound_format() {
+		let wav = b"RIFF\0\0\0\0WAVEfmt ".to_vec();
+		assert_eq!(process_sound(wav).unwrap().format, SoundFormat::Wav);
+		let ogg = b"OggS\0\x02".to_vec();
+		assert_eq!(process_sound(ogg).unwrap().format, SoundFormat::Ogg);
+		assert!(process_sound(b"\x89PNG".to_vec()).is_err());
+	}
+}
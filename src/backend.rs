@@ -1,14 +1,22 @@
 use std::collections::HashSet;
 
 use crate::prelude::*;
-use miniquad::{Context, EventHandlerFree, KeyCode, UserData, conf::Conf, date};
+use miniquad::{Context, EventHandlerFree, KeyCode, MouseButton, UserData, conf::Conf, date};
 
 pub fn plugin(app: &mut AppBuilder) {
 	app.set_runner(runner)
 		.add_event::<WindowResize>()
 		.add_event::<MouseMove>()
 		.add_event::<MouseScroll>()
+		.add_event::<TextInput>()
+		.add_event::<MouseButtonDown>()
+		.add_event::<MouseButtonUp>()
 		.add_event::<AppExit>()
+		.insert_resource(MouseCapture::default())
+		.insert_resource(MouseButtons::default())
+		.insert_resource(FrameLimiter::default())
+		.add_system(warn_graphics_settings_changed.system())
+		.add_system_to_stage(CoreStage::Last, update_mouse_capture.system())
 		.add_system_to_stage(CoreStage::Last, handle_exit_event.system());
 }
 
@@ -23,12 +31,67 @@ impl WindowSize {
 	}
 }
 
-#[derive(Default)]
+/// Settings applied when creating the rendering context. Changing them requires a restart.
+pub struct GraphicsSettings {
+	/// MSAA sample count, one of 1 (disabled), 2, 4 or 8
+	pub msaa_samples: i32,
+}
+impl Default for GraphicsSettings {
+	fn default() -> Self {
+		Self { msaa_samples: 4 }
+	}
+}
+impl GraphicsSettings {
+	const SUPPORTED_MSAA_SAMPLES: [i32; 4] = [1, 2, 4, 8];
+
+	/// Returns the largest supported sample count not above the requested one
+	fn validated_msaa_samples(&self) -> i32 {
+		let samples = Self::SUPPORTED_MSAA_SAMPLES
+			.iter()
+			.rev()
+			.find(|s| **s <= self.msaa_samples)
+			.copied()
+			.unwrap_or(1);
+		if samples != self.msaa_samples {
+			warn!(
+				"Unsupported MSAA sample count {}, using {}",
+				self.msaa_samples, samples
+			);
+		}
+		samples
+	}
+}
+
+/// Limits of the rendering context, detected when it's created
+pub struct GraphicsCapabilities {
+	/// Largest anisotropic filtering level, 0 if unsupported
+	pub max_anisotropy: f32,
+}
+impl GraphicsCapabilities {
+	/// The anisotropic filtering level textures get when `requested` is asked for
+	pub fn effective_anisotropy(&self, requested: f32) -> f32 {
+		requested.min(self.max_anisotropy).max(0.)
+	}
+}
+
 pub struct Time {
 	startup_time: f64,
 	last_update_time: Option<f64>,
 	now: f64,
 	delta: f32,
+	/// Upper bound for `delta_seconds`, so a stalled frame doesn't produce a huge step
+	pub max_delta: f32,
+}
+impl Default for Time {
+	fn default() -> Self {
+		Self {
+			startup_time: 0.,
+			last_update_time: None,
+			now: 0.,
+			delta: 0.,
+			max_delta: 0.1,
+		}
+	}
 }
 impl Time {
 	pub fn seconds_since_startup(&self) -> f64 {
@@ -38,17 +101,65 @@ impl Time {
 		self.delta
 	}
 	fn update(s: &mut Stage) {
-		let now = date::now();
-		let mut t = s.app.get_resource::<Time>();
-		t.now = now;
-		t.delta = t
+		s.app.get_resource::<Time>().advance(date::now());
+	}
+	fn advance(&mut self, now: f64) {
+		self.now = now;
+		self.delta = self
 			.last_update_time
-			.map(|last| (now - last).max(0.) as f32)
+			.map(|last| ((now - last).max(0.) as f32).min(self.max_delta))
 			.unwrap_or_default();
-		t.last_update_time = Some(now);
+		self.last_update_time = Some(now);
 	}
 }
 
+/// Cursor grab state. The cursor is captured while `requested` is set, unless the user released it.
+/// miniquad has no window focus events, so the cursor is released when pressing Alt (which also
+/// covers alt-tabbing) or minimizing the window, and grabbed again on the next click. On the web,
+/// pointer lock needs a user gesture, so a new request also waits for a click.
+#[derive(Default)]
+pub struct MouseCapture {
+	pub requested: bool,
+	released: bool,
+	just_captured: bool,
+}
+impl MouseCapture {
+	pub fn is_captured(&self) -> bool {
+		self.requested && !self.released
+	}
+
+	/// Whether the cursor was grabbed since the last frame. Mouse motion from that frame includes
+	/// the jump of the cursor back into the window, and shouldn't turn the view.
+	pub fn was_just_captured(&self) -> bool {
+		self.just_captured
+	}
+}
+
+/// Caps the frame rate by sleeping at the end of each frame. Native builds only, the browser
+/// paces frames on the web.
+#[derive(Default)]
+pub struct FrameLimiter {
+	/// Frames per second to stay under, 0 for unlimited
+	pub max_fps: u32,
+}
+impl FrameLimiter {
+	#[cfg(not(target_arch = "wasm32"))]
+	fn wait(s: &mut Stage) {
+		let max_fps = s.app.get_resource::<FrameLimiter>().max_fps;
+		if max_fps == 0 {
+			return;
+		}
+		// measured from the time the frame started, so the sleep counts towards the next delta
+		let frame_start = s.app.get_resource::<Time>().now;
+		let remaining = 1. / max_fps as f64 - (date::now() - frame_start);
+		if remaining > 0. {
+			std::thread::sleep(std::time::Duration::from_secs_f64(remaining));
+		}
+	}
+	#[cfg(target_arch = "wasm32")]
+	fn wait(_s: &mut Stage) {}
+}
+
 // events
 pub struct WindowResize {
 	pub width: f32,
@@ -61,13 +172,29 @@ pub struct MouseMove {
 pub struct MouseScroll {
 	pub delta: f32,
 }
+pub struct MouseButtonDown {
+	pub button: MouseButton,
+}
+pub struct MouseButtonUp {
+	pub button: MouseButton,
+}
+/// A typed character, including repeats from held keys
+pub struct TextInput {
+	pub character: char,
+}
 pub struct AppExit;
 
 fn runner(mut app: App) {
-	let conf = app.world.remove_resource::<Conf>().unwrap_or_default();
+	let mut conf = app.world.remove_resource::<Conf>().unwrap_or_default();
+	if let Some(graphics) = app.world.get_resource::<GraphicsSettings>() {
+		conf.sample_count = graphics.validated_msaa_samples();
+	}
 	miniquad::start(conf, |ctx| {
 		app.world
 			.insert_resource(WindowSize::new(ctx.screen_size()));
+		app.world.insert_resource(GraphicsCapabilities {
+			max_anisotropy: ctx.max_anisotropy(),
+		});
 		app.world.insert_resource(ctx);
 		app.world.insert_resource(Keyboard::default());
 		app.world.insert_resource(Time {
@@ -87,6 +214,7 @@ impl EventHandlerFree for Stage {
 		Time::update(self);
 		self.app.dispatch_update();
 		Keyboard::update(self);
+		FrameLimiter::wait(self);
 	}
 
 	fn resize_event(&mut self, width: f32, height: f32) {
@@ -107,6 +235,10 @@ impl EventHandlerFree for Stage {
 		}
 	}
 
+	fn char_event(&mut self, character: char, _keymods: miniquad::KeyMods, _repeat: bool) {
+		self.app.emit_event(TextInput { character });
+	}
+
 	fn key_up_event(&mut self, keycode: miniquad::KeyCode, _keymods: miniquad::KeyMods) {
 		self.app
 			.get_resource::<Keyboard>()
@@ -121,6 +253,20 @@ impl EventHandlerFree for Stage {
 		self.app.emit_event(MouseScroll { delta });
 	}
 
+	fn mouse_button_down_event(&mut self, button: MouseButton, _x: f32, _y: f32) {
+		self.app.get_resource::<MouseButtons>().held.insert(button);
+		self.app.emit_event(MouseButtonDown { button });
+	}
+
+	fn mouse_button_up_event(&mut self, button: MouseButton, _x: f32, _y: f32) {
+		self.app.get_resource::<MouseButtons>().held.remove(&button);
+		self.app.emit_event(MouseButtonUp { button });
+	}
+
+	fn window_minimized_event(&mut self) {
+		self.app.get_resource::<MouseCapture>().released = true;
+	}
+
 	fn draw(&mut self) {}
 }
 
@@ -143,6 +289,13 @@ impl Keyboard {
 		self.just_pressed.iter()
 	}
 
+	/// Hides all keys from the systems running after this one, for text entry that takes over
+	/// the keyboard. Keys held at the time count as released until pressed again.
+	pub fn clear(&mut self) {
+		self.just_pressed.clear();
+		self.held.clear();
+	}
+
 	fn toggle_key(&mut self, k: KeyCode, pressed: bool) {
 		if pressed {
 			self.just_pressed.insert(k);
@@ -157,8 +310,92 @@ impl Keyboard {
 	}
 }
 
+/// Mouse buttons currently held down, for press and release use the `MouseButtonDown` and
+/// `MouseButtonUp` events
+#[derive(Default, Debug)]
+pub struct MouseButtons {
+	held: HashSet<MouseButton>,
+}
+
+impl MouseButtons {
+	pub fn is_pressed(&self, button: MouseButton) -> bool {
+		self.held.contains(&button)
+	}
+}
+
+/// Grabs and hides the cursor, or frees and shows it
+fn set_mouse_capture(context: &Context, captured: bool, hidden_once: &mut bool) {
+	context.set_cursor_grab(captured);
+	let show_cursor = !captured;
+	if !show_cursor || *hidden_once {
+		// first call must be "false", or cursor remains visible
+		context.show_mouse(show_cursor);
+		*hidden_once = true;
+	}
+}
+
+fn update_mouse_capture(
+	mut capture: ResMut<MouseCapture>,
+	input: Res<Keyboard>,
+	mut clicks: EventReader<MouseButtonDown>,
+	context: Res<Context>,
+	mut applied: Local<bool>,
+	mut hidden_once: Local<bool>,
+	mut was_requested: Local<bool>,
+) {
+	#[cfg(target_arch = "wasm32")]
+	if capture.requested && !*was_requested {
+		capture.released = true;
+	}
+	*was_requested = capture.requested;
+	if input.was_just_pressed(KeyCode::LeftAlt) || input.was_just_pressed(KeyCode::RightAlt) {
+		capture.released = !capture.released;
+	}
+	if clicks.iter().count() > 0 {
+		capture.released = false;
+	}
+	let lock = capture.is_captured();
+	capture.just_captured = lock && !*applied;
+	if lock != *applied {
+		*applied = lock;
+		set_mouse_capture(&context, lock, &mut hidden_once);
+	}
+}
+
+fn warn_graphics_settings_changed(
+	settings: Option<Res<GraphicsSettings>>,
+	mut initialized: Local<bool>,
+) {
+	if let Some(settings) = settings {
+		// the first run sees the resource as changed since it was just inserted
+		if settings.is_changed() && *initialized {
+			warn!("Graphics settings changed, restart to apply them");
+		}
+		*initialized = true;
+	}
+}
+
 fn handle_exit_event(mut evt: EventReader<AppExit>, context: Res<Context>) {
 	if evt.iter().next().is_some() {
 		context.request_quit();
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn clamp_delta_after_stall() {
+		let mut time = Time {
+			max_delta: 0.1,
+			..Default::default()
+		};
+		time.advance(10.);
+		time.advance(10.016);
+		assert!((time.delta_seconds() - 0.016).abs() < 1e-4);
+		time.advance(15.016);
+		assert_eq!(time.delta_seconds(), 0.1);
+		assert!((time.seconds_since_startup() - 15.016).abs() < 1e-9);
+	}
+}
@@ -0,0 +1,73 @@
+use crate::prelude::*;
+use glam::vec2;
+use miniquad::KeyCode;
+
+pub fn plugin(app: &mut AppBuilder) {
+	app.insert_resource(FrameRate::default())
+		.add_system_to_stage(CoreStage::PreUpdate, measure_frame_rate.system())
+		.add_system(toggle_fps_counter.system())
+		.add_system(update_fps_counter.system());
+}
+
+/// Exponential moving average of the frame rate
+#[derive(Default)]
+pub struct FrameRate {
+	pub fps: f32,
+}
+
+const FPS_SMOOTHING: f32 = 0.1;
+const FPS_COUNTER_REFRESH_INTERVAL: f32 = 0.25;
+
+fn measure_frame_rate(mut rate: ResMut<FrameRate>, time: Res<Time>) {
+	let delta = time.delta_seconds();
+	// delta is zero on the first frame
+	if delta > 0. {
+		let current_fps = 1. / delta;
+		rate.fps = if rate.fps > 0. {
+			rate.fps + (current_fps - rate.fps) * FPS_SMOOTHING
+		} else {
+			current_fps
+		};
+	}
+}
+
+struct FpsCounter {
+	refresh_timer: f32,
+}
+
+fn toggle_fps_counter(
+	mut cmd: Commands,
+	input: Res<Keyboard>,
+	q: Query<Entity, With<FpsCounter>>,
+) {
+	if input.was_just_pressed(KeyCode::F1) {
+		if let Ok(counter_entity) = q.single() {
+			cmd.entity(counter_entity).despawn();
+		} else {
+			cmd.spawn_bundle((
+				FpsCounter { refresh_timer: 0. },
+				Text {
+					value: String::new(),
+					position: vec2(8., 8.),
+					scale: 3.,
+					color: Color::rgb(1., 1., 0.),
+				},
+			));
+		}
+	}
+}
+
+fn update_fps_counter(
+	mut q: Query<(&mut FpsCounter, &mut Text)>,
+	rate: Res<FrameRate>,
+	time: Res<Time>,
+) {
+	for (mut counter, mut text) in q.iter_mut() {
+		counter.refresh_timer -= time.delta_seconds();
+		if counter.refresh_timer <= 0. {
+			counter.refresh_timer = FPS_COUNTER_REFRESH_INTERVAL;
+			let frame_time = if rate.fps > 0. { 1000. / rate.fps } else { 0. };
+			text.value = format!("{:.0} FPS\n{:.1} MS", rate.fps, frame_time);
+		}
+	}
+}
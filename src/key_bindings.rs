@@ -0,0 +1,102 @@
+use crate::prelude::*;
+use miniquad::KeyCode;
+use serde_derive::Deserialize;
+use std::collections::HashMap;
+
+/// Things the player does with the keyboard, see `KeyBindings`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub enum Action {
+	MoveForward,
+	MoveBack,
+	MoveLeft,
+	MoveRight,
+	/// Held to raise the camera over the walls
+	Peek,
+	ToggleAutoWalk,
+	ToggleHover,
+	/// Turns auto-walk around to retrace its path
+	ReverseAutoWalk,
+	/// Held to speed up auto-walk
+	FastForward,
+	DropBreadcrumb,
+	ToggleExitHint,
+	ToggleLightMode,
+	WidenFieldOfView,
+	NarrowFieldOfView,
+	CycleRenderDistance,
+	ToggleFullscreen,
+	Quit,
+}
+
+/// Keys bound to each action, so the controls can be moved around for other keyboard layouts.
+/// Any of the keys bound to an action triggers it. Debug keys and text entry aren't rebindable.
+pub struct KeyBindings(HashMap<Action, Vec<KeyCode>>);
+
+impl Default for KeyBindings {
+	fn default() -> Self {
+		use Action::*;
+		Self(
+			vec![
+				(MoveForward, vec![KeyCode::W]),
+				(MoveBack, vec![KeyCode::S]),
+				(MoveLeft, vec![KeyCode::A]),
+				(MoveRight, vec![KeyCode::D]),
+				(Peek, vec![KeyCode::Tab]),
+				(ToggleAutoWalk, vec![KeyCode::Space]),
+				(ToggleHover, vec![KeyCode::X]),
+				(ReverseAutoWalk, vec![KeyCode::Backspace]),
+				(FastForward, vec![KeyCode::LeftShift]),
+				(DropBreadcrumb, vec![KeyCode::B]),
+				(ToggleExitHint, vec![KeyCode::H]),
+				(ToggleLightMode, vec![KeyCode::L]),
+				(WidenFieldOfView, vec![KeyCode::Equal, KeyCode::KpAdd]),
+				(NarrowFieldOfView, vec![KeyCode::Minus, KeyCode::KpSubtract]),
+				(CycleRenderDistance, vec![KeyCode::V]),
+				(ToggleFullscreen, vec![KeyCode::F]),
+				(Quit, vec![KeyCode::Escape]),
+			]
+			.into_iter()
+			.collect(),
+		)
+	}
+}
+
+impl KeyBindings {
+	/// Keys bound to `action`, empty if it's unbound
+	pub fn keys(&self, action: Action) -> &[KeyCode] {
+		self.0.get(&action).map_or(&[], |keys| keys.as_slice())
+	}
+
+	/// Replaces the keys bound to `action`, an empty list unbinds it
+	pub fn bind(&mut self, action: Action, keys: Vec<KeyCode>) {
+		self.0.insert(action, keys);
+	}
+
+	pub fn is_pressed(&self, action: Action, keyboard: &Keyboard) -> bool {
+		self.keys(action).iter().any(|key| keyboard.is_pressed(*key))
+	}
+
+	pub fn was_just_pressed(&self, action: Action, keyboard: &Keyboard) -> bool {
+		self.keys(action).iter().any(|key| keyboard.was_just_pressed(*key))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::HashSet;
+
+	#[test]
+	fn default_keys_trigger_one_action_each() {
+		let mut bindings = KeyBindings::default();
+		let key_count = bindings.0.values().flatten().count();
+		let unique_keys = bindings.0.values().flatten().collect::<HashSet<_>>();
+		assert_eq!(unique_keys.len(), key_count);
+
+		assert_eq!(bindings.keys(Action::Quit), &[KeyCode::Escape]);
+		bindings.bind(Action::Quit, vec![KeyCode::Q]);
+		assert_eq!(bindings.keys(Action::Quit), &[KeyCode::Q]);
+		bindings.bind(Action::Quit, vec![]);
+		assert!(bindings.keys(Action::Quit).is_empty());
+	}
+}
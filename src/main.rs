@@ -2,17 +2,21 @@
 
 mod app;
 mod assets;
+mod audio;
 mod backend;
+mod diagnostics;
+mod key_bindings;
 mod maze;
 mod maze_gen;
 mod rendering;
 mod utils;
 
-use miniquad::{KeyCode, conf::Conf};
+use key_bindings::{Action, KeyBindings};
+use miniquad::conf::Conf;
 use prelude::*;
 
 mod prelude {
-	pub use crate::{app::*, assets::*, backend::*, rendering::*, utils::*};
+	pub use crate::{app::*, assets::*, audio::*, backend::*, rendering::*, utils::*};
 	pub use bevy_ecs_wasm::prelude::*;
 	pub use crate::app::State;
 	pub use miniquad::{warn, error, info};
@@ -24,18 +28,27 @@ pub fn main() {
 			window_width: 1024,
 			window_height: 576,
 			window_title: "Maze Walk".to_string(),
-			sample_count: 2,
+			// present every display refresh (vsync), raise to cap the frame rate further
+			swap_interval: 1,
 			..Default::default()
 		})
+		.insert_resource(GraphicsSettings::default())
+		.insert_resource(KeyBindings::default())
 		.add_plugin(backend::plugin)
 		.add_plugin(rendering::plugin)
+		.add_plugin(audio::plugin)
 		.add_plugin(maze::plugin)
+		.add_plugin(diagnostics::plugin)
 		.add_system(quit_on_esc.system())
 		.run();
 }
 
-fn quit_on_esc(input: Res<Keyboard>, mut exit: EventWriter<AppExit>) {
-	if input.was_just_pressed(KeyCode::Escape) {
+fn quit_on_esc(
+	input: Res<Keyboard>,
+	bindings: Res<KeyBindings>,
+	mut exit: EventWriter<AppExit>,
+) {
+	if bindings.was_just_pressed(Action::Quit, &input) {
 		exit.send(AppExit {});
 	}
 }
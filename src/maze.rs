@@ -1,27 +1,59 @@
+mod breadcrumbs;
+mod camera_shake;
+#[cfg(debug_assertions)]
+mod chunk_printout;
+#[cfg(debug_assertions)]
+mod collision_debug;
+mod completion;
+mod console;
+mod ghost;
+mod hint;
+mod lighting_debug;
+mod loading;
+mod overhead;
+mod run_stats;
+mod save;
+mod seed;
+mod solid_color;
 mod tweaks;
+mod visited;
 
+use crate::key_bindings::{Action, KeyBindings};
 use crate::prelude::*;
-use std::cmp::Ordering;
+use std::{cmp::Ordering, collections::HashMap, ops::Range};
 
+use camera_shake::CameraShake;
+use lighting_debug::{LightingDebug, NormalMapIntensity};
+use seed::{RequestedSeed, SeedEntry};
 use tweaks::Tweaks;
 
-use crate::maze_gen::{self, GridDirection, GridMaze, GridNode};
+use crate::maze_gen::{
+	self, world_to_grid, Distances, GridDirection, GridMaze, GridNode, TupleVecConversion,
+};
 use easer::functions::{Easing, Quad as QuadEase};
 use glam::{ivec2, vec2, vec3, EulerRot, IVec2, Mat4, Quat, Vec2, Vec3};
 use miniquad::{
-	date, Comparison, Context, CullFace, FilterMode, KeyCode, PipelineParams, TextureWrap,
-	UniformType,
+	date, Comparison, Context, CullFace, FilterMode, KeyCode, MouseButton, PipelineParams,
+	TextureWrap, UniformType,
 };
 use rand::{
 	prelude::{IteratorRandom, Rng, SliceRandom, SmallRng},
 	SeedableRng,
 };
-use serde_derive::Deserialize;
+use serde_derive::{Deserialize, Serialize};
 
 pub fn plugin(app: &mut AppBuilder) {
 	#[rustfmt::skip]
 	app
+		.require_resource::<ShaderMetaStore>("maze::plugin")
+		.require_resource::<Audio>("maze::plugin")
 		.insert_resource(Tweaks::default())
+		.insert_resource(RequestedSeed::default())
+		.insert_resource(SeedEntry::default())
+		.insert_resource(save::PendingLoad::default())
+		.insert_resource(console::DevConsole::default())
+		.insert_resource(LightingDebug::default())
+		.insert_resource(LightMode::default())
 		.insert_resource(RenderSettings {
 			pipeline: PipelineParams {
 				depth_test: Comparison::LessOrEqual,
@@ -29,45 +61,116 @@ pub fn plugin(app: &mut AppBuilder) {
 				cull_face: CullFace::Back,
 				..Default::default()
 			},
+			..Default::default()
 		})
 		.register_shader_uniforms::<Uniforms>()
+		.register_shader_uniforms::<solid_color::SolidColorUniforms>()
 		.add_event::<ChunkEntered>()
 		.add_event::<ChunkExited>()
 		.add_event::<ControlModeChanged>()
+		.add_event::<camera_shake::CameraShake>()
+		.add_event::<run_stats::RunCompleted>()
 		.add_startup_system(preload_assets.system())
+		.add_startup_system(solid_color::load_solid_color_assets.system())
+		.add_startup_system(breadcrumbs::load_breadcrumb_assets.system())
+		.add_startup_system(hint::load_hint_assets.system())
+		.add_startup_system(ghost::load_ghost_assets.system())
+		.add_startup_system(visited::load_visited_assets.system())
+		.add_startup_system(loading::spawn_loading_screen.system())
+		.add_system(apply_frame_limit.system())
 		.add_system_stateful(
 			CoreStage::PreUpdate, AppState::Preload,
 			wait_for_assets_loaded.system()
 		)
+		.add_system_stateful(
+			CoreStage::Update, AppState::Preload,
+			loading::update_loading_screen.system()
+		)
+		.on_exit_state(AppState::Preload, loading::despawn_loading_screen.system())
 		.on_enter_state(AppState::Play, init_play_state.system())
+		.on_enter_state(AppState::Play, save::restore_saved_run.system())
 		.add_system_list(
 			CoreStage::Update, Some(AppState::Play),
 			SystemList::new()
+				.with(console::read_console_input.system())
+				.with(camera_shake::remove_camera_shake.system())
 				.with(auto_walk.system())
 				.with(camera_look_input.system())
 				.with(apply_euler_rotation.system())
 				.with(player_movement.system())
 				.with(collide_with_walls.system())
+				.with(snap_to_ground.system())
+				.with(peek_over_walls.system())
+				.with(camera_shake::apply_camera_shake.system())
+				.with(play_footsteps.system())
 				.with(track_current_chunk.system())
+				.with(run_stats::update_run_stats.system())
+				.with(run_stats::update_run_timer_display.system())
+				.with(ghost::record_ghost.system())
+				.with(ghost::update_ghost.system())
+				.with(visited::record_visited_cell.system())
 				.with(update_hover_mode.system())
+				.with(toggle_noclip_cheat.system())
 				.with(spawn_additional_chunk.system())
 				.with(despawn_traversed_chunks.system())
+				.with(fade_out_chunks.system())
 				.with(read_control_mode_input.system())
 				.with(update_mouse_lock.system())
 				.with(toggle_fullscreen.system())
+				.with(adjust_field_of_view.system())
+				.with(zoom_field_of_view.system())
+				.with(cycle_render_distance.system())
+				.with(lighting_debug::read_lighting_debug_input.system())
+				.with(lighting_debug::toggle_backfaces.system())
+				.with(toggle_light_mode.system())
+				.with(seed::read_seed_entry_input.system())
+				.with(seed::update_seed_display.system())
+				.with(save::save_run.system())
+				.with(save::load_run.system())
+				.with(breadcrumbs::drop_breadcrumb.system())
+				.with(completion::detect_maze_completion.system())
+				.with(hint::toggle_exit_hint.system())
+				.with(hint::update_exit_hint.system())
+				.with(overhead::toggle_overhead_view.system())
+				.with(visited::update_visited_tiles.system())
+				.with(console::update_console_display.system())
 		)
 		.on_exit_state(AppState::Play, reset_play_state.system())
+		.on_enter_state(AppState::Complete, completion::spawn_completion_overlay.system())
+		.on_enter_state(AppState::Complete, ghost::save_ghost_recording.system())
+		.add_system_stateful(
+			CoreStage::Update, AppState::Complete,
+			completion::read_restart_input.system()
+		)
+		.on_exit_state(AppState::Complete, reset_play_state.system())
 		.add_system_list(
 			CoreStage::PreRender, Some(AppState::Play),
 			SystemList::new()
 				.with(update_uniforms_from_transforms.system())
-				.with(update_uniforms_from_camera.system()),
+				.with(update_light_uniforms.system())
+				.with(lighting_debug::apply_lighting_debug.system()),
 		);
+	// debug builds only, the outlines are rebuilt every frame while shown and the printout
+	// would only spam the log of a release build
+	#[cfg(debug_assertions)]
+	app.add_system_list(
+		CoreStage::Update,
+		Some(AppState::Play),
+		SystemList::new()
+			.with(collision_debug::toggle_collision_debug.system())
+			.with(collision_debug::update_collision_debug.system())
+			.with(chunk_printout::print_current_chunk.system()),
+	);
 }
 
 const PI: f32 = std::f32::consts::PI;
 const CELL_SIZE: f32 = 1.0;
 const CHUNK_SIZE: i32 = 17;
+/// Nodes along each side of the maze in a chunk, every other grid cell is a wall
+const MAZE_SIZE: usize = (CHUNK_SIZE as usize - 1) / 2;
+const EXIT_CANDIDATE_COUNT: usize = 8;
+/// Largest `Tweaks::player_radius` that leaves room to move through a one cell corridor
+const PLAYER_RADIUS_MAX: f32 = CELL_SIZE / 2. - 0.01;
 
 fn preload_assets(
 	mut cmd: Commands,
@@ -76,6 +179,9 @@ fn preload_assets(
 	mut texture_settings: ResMut<TextureLoadSettings>,
 	mut shaders: ResMut<Assets<Shader>>,
 	mut shader_meta: ResMut<ShaderMetaStore>,
+	mut sounds: ResMut<Assets<Sound>>,
+	capabilities: Res<GraphicsCapabilities>,
+	tweaks: Res<Tweaks>,
 ) {
 	let mut rng = SmallRng::seed_from_u64(date::now() as u64);
 	let shader = shaders.load("assets/shader.glsl");
@@ -91,22 +197,31 @@ fn preload_assets(
 			("view_pos",             UniformType::Float3),
 			("light_color",          UniformType::Float3),
 			("ambient_intensity",    UniformType::Float1),
+			("ambient_color",        UniformType::Float3),
 			("object_color",         UniformType::Float3),
 			("normal_map_intensity", UniformType::Float1),
 			("specular_strength",    UniformType::Float1),
 			("shininess",            UniformType::Float1),
+			("show_normals",         UniformType::Float1),
+			("light_dir",            UniformType::Float3),
+			("is_directional",       UniformType::Float1),
+			("emission",             UniformType::Float3),
+			("fade_color",           UniformType::Float3),
+			("fade",                 UniformType::Float1),
+			("seam_blend_height",    UniformType::Float1),
 		],
 	);
 
 	let wall_colors = {
-		let num_samples = 8;
+		// chunks pick their color by index modulo the palette size
+		let num_samples = tweaks.wall_color_count.max(1);
 		let hue_offset = rng.gen_range(0.0..360.0);
 		let mut colors = (0..num_samples)
 			.map(|i| {
 				Color::hsl(
-					(((360 / num_samples) * i) as f32 + hue_offset) % 360.,
-					0.4,
-					0.8,
+					(360. / num_samples as f32 * i as f32 + hue_offset) % 360.,
+					tweaks.wall_color_saturation.clamp(0., 1.),
+					tweaks.wall_color_lightness.clamp(0., 1.),
 				)
 			})
 			.collect::<Vec<_>>();
@@ -116,17 +231,32 @@ fn preload_assets(
 
 	let floor_mesh = meshes.add(Plane::new(CHUNK_SIZE as f32, CHUNK_SIZE as f32).into());
 
-	texture_settings.set_defaults(TextureProperties {
+	let texture_defaults = TextureProperties {
 		wrap: TextureWrap::Repeat,
 		filter: FilterMode::Linear,
-		anisotropy: 8.0,
-	});
-	let wall_tex_diffuse = textures.load("assets/wall_diffuse.png");
-	let wall_tex_normal = textures.load("assets/wall_normal.png");
-	let floor_tex_diffuse = textures.load("assets/tiles_diffuse.png");
-	let floor_tex_normal = textures.load("assets/tiles_normal.png");
-	let ceiling_tex_diffuse = textures.load("assets/concrete_diffuse.png");
-	let ceiling_tex_normal = textures.load("assets/concrete_normal.png");
+		anisotropy: tweaks.anisotropy,
+		generate_mipmaps: false,
+	};
+	info!(
+		"Anisotropic filtering: {} (max supported {})",
+		capabilities.effective_anisotropy(tweaks.anisotropy),
+		capabilities.max_anisotropy
+	);
+	texture_settings.set_defaults(texture_defaults);
+	let wall_tex_diffuse = textures.load(&tweaks.wall_diffuse_texture);
+	let wall_tex_normal = textures.load(&tweaks.wall_normal_texture);
+	let floor_tex_diffuse = textures.load(&tweaks.floor_diffuse_texture);
+	let floor_tex_normal = textures.load(&tweaks.floor_normal_texture);
+	// the floor texture repeats once per cell, distant tiles shimmer without mipmaps
+	let floor_props = TextureProperties {
+		generate_mipmaps: true,
+		..texture_defaults
+	};
+	texture_settings.set(&floor_tex_diffuse, floor_props);
+	texture_settings.set(&floor_tex_normal, floor_props);
+	let ceiling_tex_diffuse = textures.load(&tweaks.ceiling_diffuse_texture);
+	let ceiling_tex_normal = textures.load(&tweaks.ceiling_normal_texture);
+	let footstep_sound = sounds.load("assets/footstep.wav");
 
 	cmd.insert_resource(MazeAssets {
 		shader,
@@ -138,6 +268,7 @@ fn preload_assets(
 		floor_tex_normal,
 		ceiling_tex_diffuse,
 		ceiling_tex_normal,
+		footstep_sound,
 	});
 }
 
@@ -151,16 +282,75 @@ struct MazeAssets {
 	floor_tex_normal: Handle<Texture>,
 	ceiling_tex_diffuse: Handle<Texture>,
 	ceiling_tex_normal: Handle<Texture>,
+	footstep_sound: Handle<Sound>,
+}
+
+/// Replaces textures that failed to load from their configured paths with the default ones.
+/// Returns true if any fallback textures were requested.
+fn load_fallback_textures(
+	assets: &mut MazeAssets,
+	textures: &mut Assets<Texture>,
+	texture_settings: &mut TextureLoadSettings,
+	tweaks: &Tweaks,
+) -> bool {
+	let defaults = Tweaks::default();
+	#[rustfmt::skip]
+	let slots = vec![
+		(&mut assets.wall_tex_diffuse,    &tweaks.wall_diffuse_texture,    defaults.wall_diffuse_texture),
+		(&mut assets.wall_tex_normal,     &tweaks.wall_normal_texture,     defaults.wall_normal_texture),
+		(&mut assets.floor_tex_diffuse,   &tweaks.floor_diffuse_texture,   defaults.floor_diffuse_texture),
+		(&mut assets.floor_tex_normal,    &tweaks.floor_normal_texture,    defaults.floor_normal_texture),
+		(&mut assets.ceiling_tex_diffuse, &tweaks.ceiling_diffuse_texture, defaults.ceiling_diffuse_texture),
+		(&mut assets.ceiling_tex_normal,  &tweaks.ceiling_normal_texture,  defaults.ceiling_normal_texture),
+	];
+	let mut fallback_requested = false;
+	for (handle, path, default_path) in slots.into_iter() {
+		if textures.get(handle).is_none() && *path != default_path {
+			warn!("Texture {} not available, using {}", path, default_path);
+			let props = texture_settings.get(handle);
+			*handle = textures.load(&default_path);
+			texture_settings.set(handle, props);
+			fallback_requested = true;
+		}
+	}
+	fallback_requested
 }
 
-struct Random(SmallRng);
+/// Seed of the current maze, each chunk is generated from it with `chunk_rng`
+struct Random {
+	seed: u64,
+	/// Index and exit of each chunk generated, in order. Replaying it reproduces the chunk chain
+	/// of a saved run, as exits depend on the chunks that existed at the time.
+	history: Vec<(usize, SidedNode)>,
+}
+
+/// Random generator for the chunk at `index` of the maze generated from `seed`. Every chunk
+/// gets its own, so a chunk stays the same however much randomness the ones before it used.
+fn chunk_rng(seed: u64, index: usize) -> SmallRng {
+	// splitmix64 finalizer, so neighboring indices and seeds don't give related streams
+	let mut z = seed ^ (index as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+	z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+	z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+	SmallRng::seed_from_u64(z ^ (z >> 31))
+}
 
 fn wait_for_assets_loaded(
 	mut state: ResMut<State>,
-	textures: Res<Assets<Texture>>,
+	mut textures: ResMut<Assets<Texture>>,
 	shaders: Res<Assets<Shader>>,
+	sounds: Res<Assets<Sound>>,
+	mut assets: ResMut<MazeAssets>,
+	mut texture_settings: ResMut<TextureLoadSettings>,
+	tweaks: Res<Tweaks>,
+	mut fallback_attempted: Local<bool>,
 ) {
-	if textures.everything_loaded() && shaders.everything_loaded() {
+	if loading::load_progress(&textures, &shaders, &sounds) >= 1. {
+		if !*fallback_attempted {
+			*fallback_attempted = true;
+			if load_fallback_textures(&mut assets, &mut textures, &mut texture_settings, &tweaks) {
+				return;
+			}
+		}
 		state.schedule_transition(AppState::Play);
 		#[cfg(target_arch = "wasm32")]
 		unsafe {
@@ -172,19 +362,27 @@ fn wait_for_assets_loaded(
 fn init_play_state(
 	mut cmd: Commands,
 	mut assets: ResMut<MazeAssets>,
-	meshes: ResMut<Assets<Mesh>>,
+	mut meshes: ResMut<Assets<Mesh>>,
 	tweaks: Res<Tweaks>,
+	mut requested_seed: ResMut<RequestedSeed>,
+	pending_load: Res<save::PendingLoad>,
 ) {
-	let mut rng = SmallRng::seed_from_u64(date::now() as u64);
+	let seed = requested_seed
+		.0
+		.take()
+		.unwrap_or_else(|| date::now() as u64);
+	let mut rng = SmallRng::seed_from_u64(seed);
 	let first_chunk = generate_chunk(
 		&mut cmd,
 		&mut assets,
-		meshes,
+		&mut meshes,
 		&tweaks,
 		0,
 		ChunkCoords::ZERO,
 		None,
-		&mut rng,
+		None,
+		&[],
+		seed,
 	);
 
 	let camera_transform = {
@@ -205,8 +403,10 @@ fn init_play_state(
 	cmd.spawn_bundle(CameraBundle {
 		transform: camera_transform.clone(),
 		camera: Camera {
-			field_of_view: 75.0,
-			clipping_distance: 0.1..100.,
+			field_of_view: tweaks.field_of_view,
+			fov_axis: tweaks.field_of_view_axis,
+			clipping_distance: clipping_distance(&tweaks),
+			..Default::default()
 		},
 		..Default::default()
 	})
@@ -220,11 +420,21 @@ fn init_play_state(
 	cmd.insert_resource(ControlMode::AutoWalk);
 	cmd.insert_resource(CurrentChunk::default());
 	cmd.insert_resource(AutoWalkState::default());
-	cmd.insert_resource(Random(rng));
+	cmd.insert_resource(overhead::OverheadView::default());
+	cmd.insert_resource(visited::VisitedCells::default());
+	cmd.insert_resource(run_stats::RunStats::default());
+	cmd.insert_resource(camera_shake::CameraShakeState::default());
+	cmd.insert_resource(Random {
+		seed,
+		history: vec![(0, first_chunk.exit.clone())],
+	});
+	seed::spawn_seed_display(&mut cmd, seed);
+	// a restored run skips the part played before saving, its time can't compete
+	ghost::start_ghost_run(&mut cmd, seed, pending_load.is_pending());
+	run_stats::spawn_run_timer(&mut cmd);
 }
 
-struct Wall;
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 struct SidedNode {
 	node: usize,
 	side: GridDirection,
@@ -242,16 +452,8 @@ struct ChunkCoords(IVec2);
 impl ChunkCoords {
 	const ZERO: ChunkCoords = ChunkCoords(IVec2::ZERO);
 	fn to_rect(self) -> Rect {
-		let (x, y) = (
-			(self.0.x * CHUNK_SIZE) as f32,
-			(self.0.y * CHUNK_SIZE) as f32,
-		);
-		Rect {
-			left: x,
-			right: x + CHUNK_SIZE as f32,
-			top: y,
-			bottom: y + CHUNK_SIZE as f32,
-		}
+		let size = Vec2::splat(CHUNK_SIZE as f32);
+		Rect::from_center(self.0.as_f32() * size + size / 2., size)
 	}
 	fn to_world_pos(self) -> Vec3 {
 		vec3(
@@ -274,18 +476,44 @@ fn camera_look_input(
 	mut q: Query<&mut RotationEuler, With<Camera>>,
 	mut mouse_move: EventReader<MouseMove>,
 	control_mode: Res<ControlMode>,
+	capture: Res<MouseCapture>,
 	tweaks: Res<Tweaks>,
+	time: Res<Time>,
+	// yaw and pitch change not yet applied when smoothing
+	mut pending: Local<Vec2>,
 ) {
-	if *control_mode != ControlMode::Manual && *control_mode != ControlMode::Hover {
+	// raw mouse motion is delivered even when the cursor is released, and is dropped rather than
+	// left for when it's captured again
+	if (*control_mode != ControlMode::Manual && *control_mode != ControlMode::Hover)
+		|| !capture.is_captured()
+		|| capture.was_just_captured()
+	{
+		mouse_move.iter().count();
+		*pending = Vec2::ZERO;
 		return;
 	}
 	let mut euler = q.single_mut().unwrap();
 	let pitch_limit = 90.0f32.to_radians() * 0.99;
 	let clamp = |f:&f32| f.clamp(-tweaks.mouse_delta_cap, tweaks.mouse_delta_cap);
+	let sign = |inverted: bool| if inverted { -1. } else { 1. };
+	let sensitivity = vec2(sign(tweaks.invert_look_x), sign(tweaks.invert_look_y))
+		* tweaks.mouse_sensitivity;
+	if tweaks.mouse_smoothing <= 0. {
+		for MouseMove { dx, dy } in mouse_move.iter() {
+			euler.yaw -= clamp(dx) * sensitivity.x;
+			euler.pitch = (euler.pitch - clamp(dy) * sensitivity.y).clamp(-pitch_limit, pitch_limit);
+		}
+		return;
+	}
 	for MouseMove { dx, dy } in mouse_move.iter() {
-		euler.yaw -= clamp(dx) * tweaks.mouse_sensitivity;
-		euler.pitch = (euler.pitch - clamp(dy) * tweaks.mouse_sensitivity).clamp(-pitch_limit, pitch_limit);
+		*pending -= vec2(clamp(dx), clamp(dy)) * sensitivity;
 	}
+	// the smoothing factor is the part left pending after a 60Hz frame
+	let retained = tweaks.mouse_smoothing.min(0.99).powf(time.delta_seconds() * 60.);
+	let applied = *pending * (1. - retained);
+	*pending -= applied;
+	euler.yaw += applied.x;
+	euler.pitch = (euler.pitch + applied.y).clamp(-pitch_limit, pitch_limit);
 }
 
 fn apply_euler_rotation(
@@ -297,74 +525,220 @@ fn apply_euler_rotation(
 }
 
 fn player_movement(
-	mut q: Query<(&mut GlobalTransform, &RotationEuler), With<Camera>>,
+	mut q: Query<&mut GlobalTransform, With<Camera>>,
 	key: Res<Keyboard>,
+	bindings: Res<KeyBindings>,
 	t: Res<Time>,
+	control_mode: Res<ControlMode>,
 ) {
+	if *control_mode == ControlMode::Overhead {
+		return;
+	}
 	let mut movement = Vec3::ZERO;
-	if key.is_pressed(KeyCode::W) {
+	if bindings.is_pressed(Action::MoveForward, &key) {
 		movement += vec3(0., 0., -1.0);
 	}
-	if key.is_pressed(KeyCode::S) {
+	if bindings.is_pressed(Action::MoveBack, &key) {
 		movement += vec3(0., 0., 1.0);
 	}
-	if key.is_pressed(KeyCode::A) {
+	if bindings.is_pressed(Action::MoveLeft, &key) {
 		movement += vec3(-1., 0., 0.);
 	}
-	if key.is_pressed(KeyCode::D) {
+	if bindings.is_pressed(Action::MoveRight, &key) {
 		movement += vec3(1., 0., 0.);
 	}
 
-	let (mut transform, euler) = q.single_mut().unwrap();
+	let mut transform = q.single_mut().unwrap();
 	if movement != Vec3::ZERO {
-		let view_relative = Quat::from_rotation_y(euler.yaw) * (movement * 3. * t.delta_seconds());
-		transform.translation += view_relative;
+		let forward = if *control_mode == ControlMode::Hover {
+			// fly along the look direction
+			transform.forward()
+		} else {
+			(transform.forward() * vec3(1., 0., 1.)).normalize_or_zero()
+		};
+		let view_relative = transform.right() * movement.x - forward * movement.z;
+		transform.translation += view_relative * 3. * t.delta_seconds();
 	}
 }
 
 fn collide_with_walls(
-	mut q: QuerySet<(
-		Query<(&GlobalTransform, &CollisionEdges), With<Wall>>,
-		Query<(&mut GlobalTransform, Option<&NoClip>), With<Camera>>,
-	)>,
+	q_walls: Query<&WallColliders>,
+	mut q_cam: Query<(&mut GlobalTransform, Option<&NoClip>, Option<&NoClipCheat>), With<Camera>>,
+	tweaks: Res<Tweaks>,
+	time: Res<Time>,
+	mut shake: EventWriter<CameraShake>,
+	mut radius_warned: Local<bool>,
+	mut touching_wall: Local<bool>,
 ) {
-	let (cam_transform, noclip) = q.q1_mut().single_mut().unwrap();
-	if noclip.is_some() {
+	let (mut cam_transform, noclip, noclip_cheat) = q_cam.single_mut().unwrap();
+	if noclip.is_some() || noclip_cheat.is_some() {
 		return;
 	}
-	let mut player_pos = cam_transform.translation;
-	let player_size = 0.2f32;
+	let player_radius = player_radius(&tweaks);
+	if player_radius != tweaks.player_radius && !*radius_warned {
+		*radius_warned = true;
+		warn!(
+			"player_radius {} clamped to {}, to fit through corridors",
+			tweaks.player_radius, player_radius
+		);
+	}
+	let player_pos = cam_transform.translation;
+	let nearby_walls = q_walls.iter().flat_map(|w| w.near(player_pos));
+	let adjusted_pos = resolve_wall_collisions(player_pos, player_radius, nearby_walls);
+	if let Some(adjusted_pos) = adjusted_pos {
+		cam_transform.translation = adjusted_pos;
+	}
+	let correction = adjusted_pos.map(|pos| pos.distance(player_pos));
+	if let Some(hit) = wall_hit_shake(&mut touching_wall, correction, time.delta_seconds()) {
+		shake.send(hit);
+	}
+}
+
+/// Speed towards a wall, in cells per second, below which touching it doesn't shake the camera
+const WALL_HIT_SHAKE_MIN_SPEED: f32 = 0.5;
+/// Speed towards a wall shaking the camera at full intensity
+const WALL_HIT_SHAKE_FULL_SPEED: f32 = 6.;
+
+/// Shake for a wall collision pushing the player back by `correction` this frame. Only the first
+/// frame of a contact shakes, so pushing against a wall doesn't keep shaking at any frame rate.
+fn wall_hit_shake(
+	touching_wall: &mut bool,
+	correction: Option<f32>,
+	delta: f32,
+) -> Option<CameraShake> {
+	let was_touching = std::mem::replace(touching_wall, correction.is_some());
+	let speed = correction? / delta.max(0.0001);
+	(!was_touching && speed > WALL_HIT_SHAKE_MIN_SPEED).then(|| CameraShake {
+		intensity: (speed / WALL_HIT_SHAKE_FULL_SPEED).min(1.),
+		duration: 0.3,
+	})
+}
+
+/// Camera clipping planes from the tweaks, or the default ones if the tweaked planes are out of
+/// order or not in front of the camera
+fn clipping_distance(tweaks: &Tweaks) -> Range<f32> {
+	let (near, far) = (tweaks.near_clip, tweaks.far_clip);
+	if near > 0. && far > near {
+		return near..far;
+	}
+	warn!("Invalid clipping planes {}..{}, using the defaults", near, far);
+	let defaults = Tweaks::default();
+	defaults.near_clip..defaults.far_clip
+}
+
+/// Half the side length of the player's square collision shape
+fn player_radius(tweaks: &Tweaks) -> f32 {
+	tweaks.player_radius.clamp(0., PLAYER_RADIUS_MAX)
+}
+
+/// Height of the floor below a position, flat everywhere until chunks have stairs or pits
+fn ground_height(_position: Vec3) -> f32 {
+	-CELL_SIZE / 2.
+}
+
+/// Keeps the camera `Tweaks::eye_height` above the ground while walking
+fn snap_to_ground(
+	mut q_cam: Query<&mut GlobalTransform, With<Camera>>,
+	control_mode: Res<ControlMode>,
+	tweaks: Res<Tweaks>,
+) {
+	// hover and overhead place the camera themselves
+	if matches!(*control_mode, ControlMode::Hover | ControlMode::Overhead) {
+		return;
+	}
+	let mut transform = q_cam.single_mut().unwrap();
+	transform.translation.y = ground_height(transform.translation) + tweaks.eye_height;
+}
+
+/// Height the camera rises to while peeking, above its position on the floor
+const PEEK_HEIGHT: f32 = 2.;
+/// Downward camera tilt at the top of a peek, in degrees
+const PEEK_TILT: f32 = 20.;
+/// Seconds to rise to the top of a peek, and to return from it
+const PEEK_DURATION: f32 = 0.3;
+
+/// Raises the camera for a look over the walls while Tab is held. Collisions keep using the
+/// position on the floor, only the height and tilt of the view change.
+fn peek_over_walls(
+	mut q_cam: Query<(&mut GlobalTransform, &RotationEuler), With<Camera>>,
+	key: Res<Keyboard>,
+	bindings: Res<KeyBindings>,
+	time: Res<Time>,
+	control_mode: Res<ControlMode>,
+	mut progress: Local<f32>,
+) {
+	// hover and overhead place the camera themselves
+	if matches!(*control_mode, ControlMode::Hover | ControlMode::Overhead) {
+		*progress = 0.;
+		return;
+	}
+	let step = time.delta_seconds() / PEEK_DURATION;
+	let previous = *progress;
+	*progress = if bindings.is_pressed(Action::Peek, &key) {
+		(previous + step).min(1.)
+	} else {
+		(previous - step).max(0.)
+	};
+	if previous == 0. && *progress == 0. {
+		return;
+	}
+	// reapplied while raised, as mouse look and auto walk reset the transform, and the height
+	// is put back to eye level every frame
+	let eased = QuadEase::ease_in_out(*progress, 0., 1., 1.);
+	let (mut transform, euler) = q_cam.single_mut().unwrap();
+	transform.translation.y += eased * PEEK_HEIGHT;
+	let pitch_limit = 90.0f32.to_radians() * 0.99;
+	let pitch = (euler.pitch - eased * PEEK_TILT.to_radians()).max(-pitch_limit);
+	transform.rotation = Quat::from_euler(EulerRot::YXZ, euler.yaw, pitch, 0.);
+}
+
+/// Pushes the player out of the given walls, returns the new position if it was adjusted
+fn resolve_wall_collisions<'a>(
+	mut player_pos: Vec3,
+	player_size: f32,
+	walls: impl Iterator<Item = (Vec3, &'a CollisionEdges)>,
+) -> Option<Vec3> {
 	let wall_size = CELL_SIZE / 2.0;
 	let mut position_adjusted = false;
-	for (
-		GlobalTransform {
-			translation: wall_pos,
-			..
-		},
-		edges,
-	) in q.q0().iter()
-	{
-		let player_rect = Rect {
-			left: player_pos.x - player_size,
-			right: player_pos.x + player_size,
-			top: player_pos.z + player_size,
-			bottom: player_pos.z - player_size,
-		};
-		let wall_rect = Rect {
-			left: wall_pos.x - wall_size,
-			right: wall_pos.x + wall_size,
-			top: wall_pos.z + wall_size,
-			bottom: wall_pos.z - wall_size,
-		};
+	for (wall_pos, edges) in walls {
+		let player_rect = Rect::from_center(
+			vec2(player_pos.x, player_pos.z),
+			Vec2::splat(player_size * 2.),
+		);
+		let wall_rect = Rect::from_center(vec2(wall_pos.x, wall_pos.z), Vec2::splat(wall_size * 2.));
 		if player_rect.intersects(wall_rect) {
-			if let Some(closest_edge) = edges.get_closest(*wall_pos, player_pos) {
-				closest_edge.clip(*wall_pos, &mut player_pos, player_size);
+			if let Some(closest_edge) = edges.get_closest(wall_pos, player_pos) {
+				closest_edge.clip(wall_pos, &mut player_pos, player_size);
 				position_adjusted = true;
 			}
 		}
 	}
-	if position_adjusted {
-		q.q1_mut().single_mut().unwrap().0.translation = player_pos;
+	position_adjusted.then(|| player_pos)
+}
+
+/// Plays a footstep sound each time the player covers `footstep_interval` along the floor
+fn play_footsteps(
+	q: Query<&GlobalTransform, With<Camera>>,
+	control_mode: Res<ControlMode>,
+	assets: Res<MazeAssets>,
+	tweaks: Res<Tweaks>,
+	mut audio: ResMut<Audio>,
+	mut last_pos: Local<Option<Vec3>>,
+	mut walked: Local<f32>,
+) {
+	let pos = q.single().unwrap().translation * vec3(1., 0., 1.);
+	let step = last_pos
+		.replace(pos)
+		.map(|last| last.distance(pos))
+		.unwrap_or_default();
+	// no footsteps while flying, or when the camera was moved to a new maze
+	if matches!(*control_mode, ControlMode::Hover | ControlMode::Overhead) || step > CELL_SIZE {
+		return;
+	}
+	*walked += step;
+	if tweaks.footstep_interval > 0. && *walked >= tweaks.footstep_interval {
+		*walked %= tweaks.footstep_interval;
+		audio.play_sound(&assets.footstep_sound);
 	}
 }
 
@@ -376,30 +750,73 @@ fn update_uniforms_from_transforms(
 	}
 }
 
-fn update_uniforms_from_camera(
+/// The headlamp is carried by the player's camera, whichever cameras the maze is drawn for
+fn update_light_uniforms(
 	mut q: QuerySet<(
-		Query<(&GlobalTransform, &ViewMatrix, &ProjectionMatrix), With<Camera>>,
+		Query<&GlobalTransform, (With<Camera>, With<RotationEuler>)>,
 		Query<&mut Uniforms>,
 	)>,
+	light_mode: Res<LightMode>,
 ) {
-	if let Ok((camera_transform, view_c, projection_c)) = q.q0().single() {
-		let (view, projection) = (view_c.0, projection_c.0);
+	if let Ok(camera_transform) = q.q0().single() {
 		let camera_position = camera_transform.translation;
 		for mut uniforms in q.q1_mut().iter_mut() {
-			uniforms.view_pos = camera_position;
-			uniforms.view = view;
-			uniforms.projection = projection;
-			uniforms.light_pos = camera_position;
+			match *light_mode {
+				LightMode::Headlamp => {
+					uniforms.light_pos = camera_position;
+					uniforms.light_color = Vec3::ONE;
+					uniforms.is_directional = 0.;
+				}
+				LightMode::Directional { direction, color } => {
+					uniforms.light_dir = direction;
+					uniforms.light_color = color;
+					uniforms.is_directional = 1.;
+				}
+			}
 		}
 	}
 }
 
+/// Light source of the maze surfaces
+#[derive(Clone, Copy, PartialEq)]
+enum LightMode {
+	/// Point light following the camera
+	Headlamp,
+	/// Constant light from far away, without attenuation
+	Directional { direction: Vec3, color: Vec3 },
+}
+
+impl Default for LightMode {
+	fn default() -> Self {
+		LightMode::Headlamp
+	}
+}
+
+fn toggle_light_mode(
+	input: Res<Keyboard>,
+	bindings: Res<KeyBindings>,
+	mut light_mode: ResMut<LightMode>,
+) {
+	if bindings.was_just_pressed(Action::ToggleLightMode, &input) {
+		*light_mode = match *light_mode {
+			LightMode::Headlamp => LightMode::Directional {
+				direction: vec3(-0.4, -1., -0.3).normalize(),
+				color: vec3(1., 0.95, 0.85),
+			},
+			LightMode::Directional { .. } => LightMode::Headlamp,
+		};
+	}
+}
+
 #[derive(Clone, Copy, PartialEq, Deserialize)]
 pub struct Material {
 	pub color: u32,
 	pub normal_intensity: f32,
 	pub specular_strength: f32,
 	pub shininess: f32,
+	/// Color added after lighting, so the surface shows in unlit areas
+	pub emissive: u32,
+	pub emissive_strength: f32,
 }
 
 impl Default for Material {
@@ -409,6 +826,8 @@ impl Default for Material {
 			normal_intensity: 0.5,
 			specular_strength: 0.5,
 			shininess: 32.0,
+			emissive: 0x000000,
+			emissive_strength: 0.,
 		}
 	}
 }
@@ -423,10 +842,29 @@ struct Uniforms {
 	light_pos: Vec3,
 	light_color: Vec3,
 	ambient_intensity: f32,
+	ambient_color: Vec3,
 	object_color: Vec3,
 	normal_map_intensity: f32,
 	specular_strength: f32,
 	shininess: f32,
+	show_normals: f32,
+	light_dir: Vec3,
+	is_directional: f32,
+	/// Emissive color premultiplied by its strength
+	emission: Vec3,
+	/// Color the surface is blended towards by `fade`
+	fade_color: Vec3,
+	fade: f32,
+	/// See `Tweaks::floor_seam_blend_height`
+	seam_blend_height: f32,
+}
+
+impl CameraUniforms for Uniforms {
+	fn set_camera(&mut self, camera: &CameraView) {
+		self.view = camera.view;
+		self.projection = camera.projection;
+		self.view_pos = camera.position;
+	}
 }
 
 impl Uniforms {
@@ -439,10 +877,18 @@ impl Uniforms {
 			light_pos: Vec3::ZERO,
 			light_color: vec3(1.0, 1.0, 1.0),
 			ambient_intensity: 0.2,
+			ambient_color: vec3(1.0, 1.0, 1.0),
 			object_color: Color::rgb_u32(m.color).into(),
 			normal_map_intensity: m.normal_intensity,
 			specular_strength: m.specular_strength,
 			shininess: m.shininess,
+			show_normals: 0.,
+			light_dir: -Vec3::Y,
+			is_directional: 0.,
+			emission: Vec3::from(Color::rgb_u32(m.emissive)) * m.emissive_strength,
+			fade_color: Vec3::ZERO,
+			fade: 0.,
+			seam_blend_height: 0.,
 		}
 	}
 }
@@ -457,6 +903,7 @@ fn update_hover_mode(
 	mut cmd: Commands,
 	mut q: Query<(Entity, &mut GlobalTransform), With<Camera>>,
 	mut mode_changed: EventReader<ControlModeChanged>,
+	tweaks: Res<Tweaks>,
 ) {
 	let (cam_entity, mut cam_transform) = q.single_mut().unwrap();
 	for changed in mode_changed.iter() {
@@ -465,23 +912,54 @@ fn update_hover_mode(
 			cam_transform.translation.y = 4.;
 		} else {
 			cmd.entity(cam_entity).remove::<NoClip>();
-			cam_transform.translation.y = 0.;
+			let ground = ground_height(cam_transform.translation);
+			cam_transform.translation.y = ground + tweaks.eye_height;
 		}
 	}
 }
 
 struct NoClip;
 
+/// Walking through walls in any control mode, toggled with Ctrl+N for debugging. Kept apart from
+/// the `NoClip` of the hover and overhead views, so leaving them doesn't turn it off.
+struct NoClipCheat;
+
+fn toggle_noclip_cheat(
+	mut cmd: Commands,
+	input: Res<Keyboard>,
+	q: Query<(Entity, Option<&NoClipCheat>), With<Camera>>,
+) {
+	let ctrl = input.is_pressed(KeyCode::LeftControl) || input.is_pressed(KeyCode::RightControl);
+	if !ctrl || !input.was_just_pressed(KeyCode::N) {
+		return;
+	}
+	let (cam_entity, cheat) = q.single().unwrap();
+	if cheat.is_some() {
+		cmd.entity(cam_entity).remove::<NoClipCheat>();
+		info!("No-clip off");
+	} else {
+		cmd.entity(cam_entity).insert(NoClipCheat);
+		info!("No-clip on");
+	}
+}
+
 fn read_control_mode_input(
 	mut current: ResMut<ControlMode>,
 	input: Res<Keyboard>,
+	bindings: Res<KeyBindings>,
 	mut changed: EventWriter<ControlModeChanged>,
 ) {
-	let pressed_state = input.get_just_pressed().next().and_then(|key| match key {
-		KeyCode::Space => Some(ControlMode::AutoWalk),
-		KeyCode::X => Some(ControlMode::Hover),
-		_ => None,
-	});
+	// left through the overhead view toggle only
+	if *current == ControlMode::Overhead {
+		return;
+	}
+	let pressed_state = if bindings.was_just_pressed(Action::ToggleAutoWalk, &input) {
+		Some(ControlMode::AutoWalk)
+	} else if bindings.was_just_pressed(Action::ToggleHover, &input) {
+		Some(ControlMode::Hover)
+	} else {
+		None
+	};
 
 	if let Some(mut target_state) = pressed_state {
 		if target_state == *current {
@@ -492,42 +970,131 @@ fn read_control_mode_input(
 	}
 }
 
-fn update_mouse_lock(mode: Res<ControlMode>, ctx: ResMut<Context>, mut hidden_once: Local<bool>) {
+fn update_mouse_lock(mode: Res<ControlMode>, mut capture: ResMut<MouseCapture>) {
 	if mode.is_changed() {
-		let lock = match *mode {
-			ControlMode::AutoWalk => false,
+		capture.requested = match *mode {
+			ControlMode::AutoWalk | ControlMode::Overhead => false,
 			ControlMode::Manual | ControlMode::Hover => true,
 		};
-		ctx.set_cursor_grab(lock);
-		let show_cursor = !lock;
-		if !show_cursor || *hidden_once {
-			// first call must be "false", or cursor remains visible
-			ctx.show_mouse(show_cursor);
-			*hidden_once = true;
-		}
 	}
 }
 
-#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
 enum ControlMode {
 	Manual,
 	Hover,
 	AutoWalk,
+	/// Top-down view of the current chunk, for debugging
+	Overhead,
 }
 
 struct ControlModeChanged(ControlMode);
 
 fn toggle_fullscreen(
 	input: Res<Keyboard>,
+	bindings: Res<KeyBindings>,
 	mut is_fullscreen: Local<bool>,
 	context: ResMut<Context>,
 ) {
-	if input.was_just_pressed(KeyCode::F) {
+	if bindings.was_just_pressed(Action::ToggleFullscreen, &input) {
 		*is_fullscreen = !*is_fullscreen;
 		context.set_fullscreen(*is_fullscreen);
 	}
 }
 
+const FIELD_OF_VIEW_MIN: f32 = 60.;
+const FIELD_OF_VIEW_MAX: f32 = 110.;
+const FIELD_OF_VIEW_STEP: f32 = 5.;
+
+fn adjust_field_of_view(
+	input: Res<Keyboard>,
+	bindings: Res<KeyBindings>,
+	control_mode: Res<ControlMode>,
+	mut tweaks: ResMut<Tweaks>,
+	mut q: Query<&mut Camera>,
+) {
+	// the overhead view uses an orthographic projection
+	if *control_mode == ControlMode::Overhead {
+		return;
+	}
+	let mut step = 0.;
+	if bindings.was_just_pressed(Action::WidenFieldOfView, &input) {
+		step += FIELD_OF_VIEW_STEP;
+	}
+	if bindings.was_just_pressed(Action::NarrowFieldOfView, &input) {
+		step -= FIELD_OF_VIEW_STEP;
+	}
+	if step == 0. {
+		return;
+	}
+	let mut camera = q.single_mut().unwrap();
+	let field_of_view = (camera.field_of_view + step).clamp(FIELD_OF_VIEW_MIN, FIELD_OF_VIEW_MAX);
+	if field_of_view != camera.field_of_view {
+		camera.field_of_view = field_of_view;
+		// keep it for the cameras of subsequent mazes
+		tweaks.field_of_view = field_of_view;
+	}
+}
+
+/// Narrowest field of view reached when zooming in with the mouse wheel
+const ZOOM_FIELD_OF_VIEW_MIN: f32 = 10.;
+/// Field of view change per mouse wheel notch while zooming
+const ZOOM_FACTOR: f32 = 1.25;
+
+/// Holding the right mouse button lets the mouse wheel narrow the field of view, for spotting
+/// distant exits. The previous field of view is restored on release.
+fn zoom_field_of_view(
+	buttons: Res<MouseButtons>,
+	mut scroll: EventReader<MouseScroll>,
+	control_mode: Res<ControlMode>,
+	mut q: Query<&mut Camera>,
+	// field of view before zooming
+	mut restored: Local<Option<f32>>,
+) {
+	// read even when not zooming, so earlier scrolling doesn't apply once zooming starts
+	let notches = scroll.iter().map(|s| s.delta.signum()).sum::<f32>();
+	let mut camera = q.single_mut().unwrap();
+	// the overhead view uses an orthographic projection
+	let zooming = buttons.is_pressed(MouseButton::Right) && *control_mode != ControlMode::Overhead;
+	if !zooming {
+		if let Some(field_of_view) = restored.take() {
+			camera.field_of_view = field_of_view;
+		}
+		return;
+	}
+	let max = *restored.get_or_insert(camera.field_of_view);
+	if notches != 0. {
+		// scrolling up zooms in
+		let field_of_view = camera.field_of_view / ZOOM_FACTOR.powf(notches);
+		camera.field_of_view = field_of_view.clamp(ZOOM_FIELD_OF_VIEW_MIN, max);
+	}
+}
+
+/// Passes `Tweaks::max_fps` on to the backend, including changes made while running
+fn apply_frame_limit(tweaks: Res<Tweaks>, mut limiter: ResMut<FrameLimiter>) {
+	if tweaks.is_changed() {
+		limiter.max_fps = tweaks.max_fps;
+	}
+}
+
+/// Low, medium and high settings cycled through at runtime
+const RENDER_DISTANCE_PRESETS: [usize; 3] = [1, 2, 4];
+
+fn cycle_render_distance(
+	input: Res<Keyboard>,
+	bindings: Res<KeyBindings>,
+	mut tweaks: ResMut<Tweaks>,
+) {
+	if bindings.was_just_pressed(Action::CycleRenderDistance, &input) {
+		tweaks.render_distance = RENDER_DISTANCE_PRESETS
+			.iter()
+			.copied()
+			.find(|&preset| preset > tweaks.render_distance)
+			.unwrap_or(RENDER_DISTANCE_PRESETS[0]);
+		info!("Render distance: {} chunks", tweaks.render_distance);
+	}
+}
+
 #[derive(Clone, Copy, Debug)]
 enum CollisionEdge {
 	NegX,
@@ -600,83 +1167,168 @@ fn track_current_chunk(
 	}
 }
 
+/// Keeps `chunk_lookahead` chunks generated ahead of the current one. At most one chunk is
+/// generated per frame, to spread the cost of catching up over several frames.
 fn spawn_additional_chunk(
 	mut cmd: Commands,
 	mut assets: ResMut<MazeAssets>,
-	meshes: ResMut<Assets<Mesh>>,
+	mut meshes: ResMut<Assets<Mesh>>,
 	tweaks: Res<Tweaks>,
-	q: Query<(Entity, &Chunk)>,
-	mut entered_event: EventReader<ChunkEntered>,
-	mut rng: ResMut<Random>,
+	q: Query<&Chunk>,
+	current_chunk: Res<CurrentChunk>,
+	mut random: ResMut<Random>,
 ) {
-	let (last_chunk_ent, last_chunk_data) = q
-		.iter()
-		.max_by_key(|(_, c)| c.index)
-		.expect("get last chunk");
-	let entered_last_chunk = entered_event
-		.iter()
-		.any(|ChunkEntered(e)| *e == last_chunk_ent);
-	if entered_last_chunk {
-		let (next_chunk_coords, next_chunk_entrance) = {
-			let base_chunk = last_chunk_data;
-			let next_chunk_dir: IVec2 = base_chunk.exit.side.get_offset().into();
-			let exit_pos: IVec2 = base_chunk.maze.idx_to_pos(base_chunk.exit.node).into();
-			let next_chunk_coords = base_chunk.coords.0 + next_chunk_dir;
-			let maze_size = base_chunk.maze.dimensions().0 as i32;
-			let entrance_pos = (base_chunk.coords.0 * maze_size + exit_pos + next_chunk_dir)
-				- next_chunk_coords * maze_size;
-			debug_assert!(
-				entrance_pos.x >= 0
-					&& entrance_pos.y >= 0
-					&& entrance_pos.x < maze_size
-					&& entrance_pos.y < maze_size
-			);
-			let entrance_index = GridMaze::idx_1d(
-				entrance_pos.y as usize,
-				entrance_pos.x as usize,
-				maze_size as usize,
-			);
-			(
-				ChunkCoords(next_chunk_coords),
-				SidedNode {
-					node: entrance_index,
-					side: base_chunk.exit.side.opposite(),
-				},
-			)
-		};
-
-		generate_chunk(
+	let last_chunk_data = q.iter().max_by_key(|c| c.index).expect("get last chunk");
+	let current_index = current_chunk
+		.0
+		.and_then(|e| q.get(e).ok())
+		.map_or(0, |c| c.index);
+	let lookahead_missing = last_chunk_data.index < current_index + chunk_lookahead(&tweaks);
+	let reached_maze_length = tweaks
+		.maze_length
+		.map_or(false, |length| last_chunk_data.index + 1 >= length);
+	if lookahead_missing && !reached_maze_length {
+		let (next_chunk_coords, next_chunk_entrance) = next_chunk_placement(last_chunk_data);
+		let occupied_coords = q.iter().map(|c| c.coords).collect::<Vec<_>>();
+		let index = last_chunk_data.index + 1;
+		let chunk = generate_chunk(
 			&mut cmd,
 			&mut assets,
-			meshes,
+			&mut meshes,
 			&tweaks,
-			last_chunk_data.index + 1,
+			index,
 			next_chunk_coords,
 			Some(next_chunk_entrance),
-			&mut rng.0,
+			None,
+			&occupied_coords,
+			random.seed,
 		);
+		random.history.push((index, chunk.exit));
 	}
 }
 
+/// Coordinates and entrance of the chunk connected to the exit of `base_chunk`
+fn next_chunk_placement(base_chunk: &Chunk) -> (ChunkCoords, SidedNode) {
+	let next_chunk_dir: IVec2 = base_chunk.exit.side.get_offset().into();
+	let exit_pos: IVec2 = base_chunk.maze.idx_to_pos(base_chunk.exit.node).into();
+	let next_chunk_coords = base_chunk.coords.0 + next_chunk_dir;
+	let maze_size = base_chunk.maze.dimensions().0 as i32;
+	let entrance_pos = (base_chunk.coords.0 * maze_size + exit_pos + next_chunk_dir)
+		- next_chunk_coords * maze_size;
+	debug_assert!(
+		entrance_pos.x >= 0
+			&& entrance_pos.y >= 0
+			&& entrance_pos.x < maze_size
+			&& entrance_pos.y < maze_size
+	);
+	let entrance_index = GridMaze::idx_1d(
+		entrance_pos.y as usize,
+		entrance_pos.x as usize,
+		maze_size as usize,
+	);
+	(
+		ChunkCoords(next_chunk_coords),
+		SidedNode {
+			node: entrance_index,
+			side: base_chunk.exit.side.opposite(),
+		},
+	)
+}
+
+/// Number of chunks kept generated ahead of the current one, see `Tweaks::chunk_lookahead`
+fn chunk_lookahead(tweaks: &Tweaks) -> usize {
+	tweaks.chunk_lookahead.max(1).min(tweaks.render_distance.max(1))
+}
+
+/// Number of older chunks kept behind the current one, see `Tweaks::chunks_behind`
+fn chunks_behind(tweaks: &Tweaks) -> usize {
+	tweaks.chunks_behind.unwrap_or(tweaks.render_distance.max(1) - 1)
+}
+
+/// Removes chunks more than `chunk_lookahead` chunks ahead of the current one, or more than
+/// `chunks_behind` behind it and farther than `chunk_keep_distance` from the camera. Chunks
+/// behind fade out, while excess chunks ahead are despawned at once so the last chunk always
+/// continues the entrance/exit chain.
 fn despawn_traversed_chunks(
 	mut cmd: Commands,
 	q_chunks: Query<(Entity, &Chunk)>,
-	mut entered_event: EventReader<ChunkEntered>,
+	fading: Query<Entity, With<Fading>>,
+	q_cam: Query<&GlobalTransform, With<Camera>>,
+	current_chunk: Res<CurrentChunk>,
+	auto_walk: Res<AutoWalkState>,
+	tweaks: Res<Tweaks>,
 ) {
-	for ChunkEntered(entered_ent) in entered_event.iter() {
-		let entered_index = q_chunks
-			.get(*entered_ent)
-			.expect("resolve entered chunk")
-			.1
-			.index;
+	// chunks behind the player are needed again when walking back through them
+	if auto_walk.reversing {
+		return;
+	}
+	if let Some(current_index) = current_chunk
+		.0
+		.and_then(|e| q_chunks.get(e).ok())
+		.map(|(_, c)| c.index)
+	{
+		// the current chunk and the one past its exit are always kept
+		let lookahead = chunk_lookahead(&tweaks);
+		let chunks_behind = chunks_behind(&tweaks);
+		let cam_pos = q_cam.single().unwrap().translation;
+		let cam_pos = vec2(cam_pos.x, cam_pos.z);
 		for (ent, chunk) in q_chunks.iter() {
-			if entered_index > 0 && chunk.index < entered_index - 1 {
+			if chunk.index > current_index + lookahead {
 				cmd.entity(ent).despawn_recursive();
+				continue;
+			}
+			let is_old = chunk.index + chunks_behind < current_index;
+			let is_far =
+				chunk.coords.to_rect().center().distance(cam_pos) > tweaks.chunk_keep_distance;
+			if is_old && is_far && fading.get(ent).is_err() {
+				cmd.entity(ent).insert(Fading { t: 0. });
 			}
 		}
 	}
 }
 
+/// Chunk dimming out to the background color before it's despawned
+struct Fading {
+	t: f32,
+}
+
+fn fade_out_chunks(
+	mut cmd: Commands,
+	mut q_chunks: Query<(Entity, &mut Fading, &Children)>,
+	mut q_uniforms: Query<&mut Uniforms>,
+	mut q_markers: Query<&mut solid_color::SolidColorUniforms>,
+	current_chunk: Res<CurrentChunk>,
+	render_settings: Res<RenderSettings>,
+	time: Res<Time>,
+	tweaks: Res<Tweaks>,
+) {
+	let [r, g, b, _] = render_settings.clear_color.as_linear_rgba_f32();
+	for (entity, mut fading, children) in q_chunks.iter_mut() {
+		// walked back into before it was gone
+		let cancelled = current_chunk.0 == Some(entity);
+		fading.t = if cancelled {
+			0.
+		} else {
+			(fading.t + time.delta_seconds() / tweaks.chunk_fade_duration.max(0.0001)).min(1.)
+		};
+		for surface in std::iter::once(entity).chain(children.0.iter().copied()) {
+			if let Ok(mut uniforms) = q_uniforms.get_mut(surface) {
+				uniforms.fade_color = vec3(r, g, b);
+				uniforms.fade = fading.t;
+			}
+			// breadcrumbs and visited tiles are unlit, they thin out instead
+			if let Ok(mut uniforms) = q_markers.get_mut(surface) {
+				uniforms.alpha = 1. - fading.t;
+			}
+		}
+		if cancelled {
+			cmd.entity(entity).remove::<Fading>();
+		} else if fading.t >= 1. {
+			cmd.entity(entity).despawn_recursive();
+		}
+	}
+}
+
 #[derive(Default)]
 struct AutoWalkState {
 	translation_from: Vec3,
@@ -684,7 +1336,39 @@ struct AutoWalkState {
 	rotation_from: f32,
 	rotation_to: f32,
 	tween_progress: Option<f32>,
+	/// Progress of the turn towards `rotation_to`, advanced separately from the translation
+	rotation_progress: f32,
 	heading: Option<GridDirection>,
+	/// Retracing the walked path back towards the earlier chunks
+	reversing: bool,
+}
+
+impl AutoWalkState {
+	fn start_step(&mut self, translation: (Vec3, Vec3), rotation: (f32, f32)) {
+		self.translation_from = translation.0;
+		self.translation_to = translation.1;
+		self.rotation_from = rotation.0;
+		self.rotation_to = rotation.1;
+		self.tween_progress = Some(0.);
+		self.rotation_progress = 0.;
+	}
+
+	/// Advances the current step by `delta` seconds, returns the camera position and yaw
+	fn advance(&mut self, delta: f32, tweaks: &Tweaks) -> Option<(Vec3, f32)> {
+		let t = self.tween_progress?;
+		// conserve movement speed during chunk transitions (3 blocks)
+		let walk_distance = self.translation_from.distance(self.translation_to).max(0.0001);
+		let t = (t + delta * tweaks.auto_walk_speed / walk_distance).min(1.0);
+		// turns take the same time on short steps and on the long ones into the next chunk
+		let turn_duration = tweaks.auto_walk_turn_duration.max(0.0001);
+		self.rotation_progress = (self.rotation_progress + delta / turn_duration).min(1.0);
+		let rotation_t = QuadEase::ease_in_out(self.rotation_progress, 0., 1., 1.);
+		self.tween_progress = (t < 1.0).then(|| t);
+		Some((
+			self.translation_from.lerp(self.translation_to, t),
+			lerp_angle(self.rotation_from, self.rotation_to, rotation_t),
+		))
+	}
 }
 
 fn auto_walk(
@@ -696,43 +1380,58 @@ fn auto_walk(
 	control_mode: Res<ControlMode>,
 	mut mode_changed: EventReader<ControlModeChanged>,
 	input: Res<Keyboard>,
+	bindings: Res<KeyBindings>,
+	tweaks: Res<Tweaks>,
+	// chunk and target node the distances were computed for, in solve mode
+	mut solved: Local<Option<(Entity, usize, Distances)>>,
 ) {
 	let (mut cam_transform, mut cam_euler) = q_cam.single_mut().expect("get camera position");
 	for mode in mode_changed.iter() {
 		if mode.0 != ControlMode::AutoWalk {
 			state.heading = None;
 			state.tween_progress = None;
+			state.reversing = false;
+		}
+	}
+	if *control_mode == ControlMode::AutoWalk
+		&& bindings.was_just_pressed(Action::ReverseAutoWalk, &input)
+	{
+		// following the wall on the other hand in the opposite heading retraces the path exactly
+		state.reversing = !state.reversing;
+		if let Some(reversed) = state.heading.map(GridDirection::opposite) {
+			state.heading = Some(reversed);
+			if state.tween_progress.is_some() {
+				// head back to the node the current step started from
+				let step_start = state.translation_from;
+				state.start_step(
+					(cam_transform.translation, step_start),
+					(
+						cam_euler.yaw + tweaks.auto_walk_reverse_bias,
+						reversed.to_vec2().angle_between(-Vec2::Y),
+					),
+				);
+			}
 		}
 	}
 	if *control_mode == ControlMode::AutoWalk {
-		if let Some(mut t) = state.tween_progress {
-			let delta = time.delta_seconds()
-				* (if input.is_pressed(KeyCode::LeftShift) {
-					5.
-				} else {
-					1.
-				});
-			// conserve movement speed during chunk transitions (3 blocks)
-			let walk_distance = state.translation_from.distance(state.translation_to);
-			let tween_duration_multiplier = 2.0 / walk_distance.max(0.0001);
-			t = (t + delta * tween_duration_multiplier).min(1.0);
-			cam_transform.translation = state.translation_from.lerp(state.translation_to, t);
-			let rotation_t = QuadEase::ease_in_out((t * 2.).min(1.0), 0., 1., 1.);
-			cam_euler.yaw = lerp_angle(state.rotation_from, state.rotation_to, rotation_t);
+		let delta = time.delta_seconds()
+			* (if bindings.is_pressed(Action::FastForward, &input) {
+				5.
+			} else {
+				1.
+			});
+		if let Some((translation, yaw)) = state.advance(delta, &tweaks) {
+			cam_transform.translation = translation;
+			cam_euler.yaw = yaw;
 			cam_euler.pitch = 0.;
-			state.tween_progress = (t < 1.0).then(|| t);
 		}
 		if state.tween_progress.is_none() {
 			if let Some(current_chunk_ent) = current_chunk_res.0 {
 				let (_, current_chunk) = q_chunks
 					.get(current_chunk_ent)
 					.expect("resolve current chunk");
-				let cam_pos_relative_to_grid =
-					cam_transform.translation - current_chunk.coords.to_world_pos();
-				let cam_grid_pos = (
-					cam_pos_relative_to_grid.x.round() as i32,
-					cam_pos_relative_to_grid.z.round() as i32,
-				);
+				let cam_grid_pos =
+					world_to_grid(cam_transform.translation - current_chunk.coords.to_world_pos());
 				if let Some(node_near_camera) = current_chunk
 					.maze
 					.pos_to_idx(grid_to_maze(cam_grid_pos))
@@ -755,6 +1454,7 @@ fn auto_walk(
 
 					let previous_heading = state.heading.unwrap_or_else(get_direction_from_camera);
 					let is_first_step = state.heading.is_none();
+					let reversing = state.reversing;
 
 					let heading = {
 						let get_linked_neighbor_position = |dir: GridDirection| {
@@ -766,6 +1466,15 @@ fn auto_walk(
 									.iter()
 									.find(|(_, c)| c.index == current_chunk.index + 1)
 									.map(|(_, c)| node_to_world(&c.maze[c.entrance.node], &c))
+							} else if reversing
+								&& node_near_camera.idx() == current_chunk.entrance.node
+								&& dir == current_chunk.entrance.side
+							{
+								// previous chunk exit, unless it was despawned before reversing
+								q_chunks
+									.iter()
+									.find(|(_, c)| c.index + 1 == current_chunk.index)
+									.map(|(_, c)| node_to_world(&c.maze[c.exit.node], &c))
 							} else if let (true, Some(neighbor_node)) = (
 								maze.has_link(&node_near_camera, dir),
 								maze.get_neighbor(&node_near_camera, dir),
@@ -777,32 +1486,51 @@ fn auto_walk(
 								None
 							}
 						};
-						// test walkable directions
-						let mut valid_heading = None;
-						let mut current_dir = previous_heading;
-						if !is_first_step {
-							current_dir = current_dir.rotate_cw();
-						}
-						for _ in 0..4 {
-							if let Some(pos) = get_linked_neighbor_position(current_dir) {
-								valid_heading = Some((current_dir, pos));
-								break;
-							} else {
-								current_dir = current_dir.rotate_ccw();
+						match tweaks.auto_walk_mode {
+							AutoWalkMode::WallFollow => choose_heading(
+								if reversing {
+									tweaks.auto_walk_hand.opposite()
+								} else {
+									tweaks.auto_walk_hand
+								},
+								previous_heading,
+								is_first_step,
+								get_linked_neighbor_position,
+							),
+							AutoWalkMode::Solve => {
+								let target = if reversing {
+									&current_chunk.entrance
+								} else {
+									&current_chunk.exit
+								};
+								let is_cached = matches!(*solved, Some((entity, node, _))
+									if entity == current_chunk_ent && node == target.node);
+								if !is_cached {
+									let distances = maze.distances(&maze[target.node]);
+									*solved = Some((current_chunk_ent, target.node, distances));
+								}
+								let (_, _, distances) = solved.as_ref().unwrap();
+								choose_solving_heading(
+									maze,
+									&node_near_camera,
+									target,
+									distances,
+									get_linked_neighbor_position,
+								)
 							}
 						}
-						valid_heading
 					};
 
 					if let Some((direction, neighbor_node_position)) = heading {
 						state.heading = Some(direction);
-						state.translation_from = cam_transform.translation;
-						state.translation_to = neighbor_node_position;
-						let always_turn_left_when_reversing_bias = 0.001;
-						state.rotation_from = cam_euler.yaw + always_turn_left_when_reversing_bias;
-						state.rotation_to =
-							direction.get_offset().to_vec2().angle_between(-Vec2::Y);
-						state.tween_progress = Some(0.);
+						// always turn left when reversing, instead of depending on rounding
+						state.start_step(
+							(cam_transform.translation, neighbor_node_position),
+							(
+								cam_euler.yaw + tweaks.auto_walk_reverse_bias,
+								direction.to_vec2().angle_between(-Vec2::Y),
+							),
+						);
 					}
 				}
 			}
@@ -810,24 +1538,139 @@ fn auto_walk(
 	}
 }
 
-fn reset_play_state(mut cmd: Commands, q: Query<Entity, With<Reset>>) {
+/// How auto-walk finds its way through a chunk
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub enum AutoWalkMode {
+	/// Keeps a hand on the wall, which explores dead ends on the way, see `AutoWalkHand`
+	WallFollow,
+	/// Takes the shortest path to the exit, or back to the entrance when reversing
+	Solve,
+}
+
+/// The wall auto-walk keeps its hand on
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub enum AutoWalkHand {
+	Left,
+	Right,
+}
+
+impl AutoWalkHand {
+	fn opposite(self) -> Self {
+		match self {
+			AutoWalkHand::Left => AutoWalkHand::Right,
+			AutoWalkHand::Right => AutoWalkHand::Left,
+		}
+	}
+
+	fn towards_wall(self, dir: GridDirection) -> GridDirection {
+		match self {
+			AutoWalkHand::Left => dir.rotate_ccw(),
+			AutoWalkHand::Right => dir.rotate_cw(),
+		}
+	}
+
+	fn away_from_wall(self, dir: GridDirection) -> GridDirection {
+		match self {
+			AutoWalkHand::Left => dir.rotate_cw(),
+			AutoWalkHand::Right => dir.rotate_ccw(),
+		}
+	}
+}
+
+/// Picks the next direction to walk in by following the wall on the given hand side: the first
+/// walkable direction turning away from the wall, starting towards the wall from the previous
+/// heading (or at the previous heading on the first step). Returns the direction and the
+/// position to walk to.
+fn choose_heading(
+	hand: AutoWalkHand,
+	previous_heading: GridDirection,
+	is_first_step: bool,
+	linked_neighbor_position: impl Fn(GridDirection) -> Option<Vec3>,
+) -> Option<(GridDirection, Vec3)> {
+	let mut current_dir = previous_heading;
+	if !is_first_step {
+		current_dir = hand.towards_wall(current_dir);
+	}
+	for _ in 0..4 {
+		if let Some(pos) = linked_neighbor_position(current_dir) {
+			return Some((current_dir, pos));
+		}
+		current_dir = hand.away_from_wall(current_dir);
+	}
+	None
+}
+
+/// Picks the linked direction towards the neighbor closest to `target` by `distances`, which
+/// are measured from the target node. Leaves through the target side once at the target node.
+/// Returns the direction and the position to walk to.
+fn choose_solving_heading(
+	maze: &GridMaze,
+	node: &GridNode,
+	target: &SidedNode,
+	distances: &Distances,
+	linked_neighbor_position: impl Fn(GridDirection) -> Option<Vec3>,
+) -> Option<(GridDirection, Vec3)> {
+	if node.idx() == target.node {
+		return linked_neighbor_position(target.side).map(|pos| (target.side, pos));
+	}
+	GridDirection::ALL
+		.iter()
+		.filter_map(|&dir| {
+			let distance = *distances.get(&maze.get_neighbor(node, dir)?)?;
+			linked_neighbor_position(dir).map(|pos| (distance, dir, pos))
+		})
+		.min_by_key(|(distance, _, _)| *distance)
+		.map(|(_, dir, pos)| (dir, pos))
+}
+
+fn reset_play_state(mut cmd: Commands, q: Query<Entity, With<Reset>>, state: Res<State>) {
+	// keep the finished maze in view behind the completion overlay
+	if state.get_pending() == Some(AppState::Complete) {
+		return;
+	}
 	for e in q.iter() {
 		cmd.entity(e).despawn_recursive();
 	}
 }
 
-fn generate_chunk(
-	cmd: &mut Commands,
-	assets: &mut MazeAssets,
-	mut meshes: ResMut<Assets<Mesh>>,
+/// Maze and wall blocks of a chunk, before any entities are spawned for it
+struct ChunkLayout {
+	maze: GridMaze,
+	entrance: SidedNode,
+	exit: SidedNode,
+	/// Wall blocks by grid cell, indexed by z, then x
+	grid: [[bool; CHUNK_SIZE as usize]; CHUNK_SIZE as usize],
+}
+
+impl ChunkLayout {
+	fn has_block(&self, pos: IVec2) -> bool {
+		pos.x >= 0
+			&& pos.x < CHUNK_SIZE
+			&& pos.y >= 0
+			&& pos.y < CHUNK_SIZE
+			&& self.grid[pos.y as usize][pos.x as usize]
+	}
+}
+
+/// Generates the maze of a chunk, selects its entrance and exit and carves the wall grid.
+/// The entrance passage is only opened if `known_entrance` connects to a previous chunk.
+/// A `known_exit` replaces the selected exit, without changing how much of `rng` is used.
+fn generate_chunk_layout(
 	tweaks: &Tweaks,
-	index: usize,
 	coords: ChunkCoords,
 	known_entrance: Option<SidedNode>,
+	known_exit: Option<SidedNode>,
+	occupied_coords: &[ChunkCoords],
 	rng: &mut impl Rng,
-) -> Chunk {
-	const MAZE_SIZE: usize = (CHUNK_SIZE as usize - 1) / 2;
-	let maze = maze_gen::generate(MAZE_SIZE, MAZE_SIZE, rng);
+) -> ChunkLayout {
+	let mut maze = maze_gen::generate(MAZE_SIZE, MAZE_SIZE, rng);
+	// costlier cells steer the exit selection and the exit hint path
+	maze.add_weighted_regions(
+		tweaks.difficult_region_count,
+		1,
+		tweaks.difficult_region_weight,
+		rng,
+	);
 	let mut grid = {
 		let mut grid = [[true; CHUNK_SIZE as usize]; CHUNK_SIZE as usize];
 		for (maze_z, row) in maze.iter_rows().enumerate() {
@@ -848,7 +1691,11 @@ fn generate_chunk(
 	let make_entrance_passage = known_entrance.is_some();
 	let (entrance, exit) = {
 		let entrance = known_entrance.unwrap_or_else(|| {
-			let side = GridDirection::ALL[rng.gen_range(0..4)];
+			let side = match tweaks.start_facing {
+				// walking in from the opposite side
+				Some(facing) => facing.opposite(),
+				None => GridDirection::ALL[rng.gen_range(0..4)],
+			};
 			SidedNode {
 				node: maze
 					.get_edge_nodes(side)
@@ -859,24 +1706,63 @@ fn generate_chunk(
 			}
 		});
 		let distances = maze.distances(&maze[entrance.node]);
-		let exit_pair = GridDirection::ALL
+		// keep the chunk chain from running into existing chunks, unless it's boxed in
+		let exit_sides = {
+			let sides = GridDirection::ALL
+				.iter()
+				.filter(|d| **d != entrance.side)
+				.copied()
+				.collect::<Vec<_>>();
+			let unobstructed = sides
+				.iter()
+				.filter(|d| {
+					let next_coords = coords.0 + d.get_offset().to_ivec2();
+					!occupied_coords.iter().any(|c| c.0 == next_coords)
+				})
+				.copied()
+				.collect::<Vec<_>>();
+			if unobstructed.is_empty() {
+				sides
+			} else {
+				unobstructed
+			}
+		};
+		let mut exit_candidates = exit_sides
 			.iter()
-			.filter(|d| **d != entrance.side)
 			.flat_map(|d| {
 				maze.get_edge_nodes(*d)
 					.iter()
 					.map(|n| (n.idx(), *d))
 					.collect::<Vec<_>>()
 			})
-			.max_by_key(|p| distances.get(&maze[p.0]))
+			.collect::<Vec<_>>();
+		// farthest by path first
+		exit_candidates.sort_by_key(|p| std::cmp::Reverse(distances.get(&maze[p.0])));
+		exit_candidates.truncate(EXIT_CANDIDATE_COUNT);
+		let entrance_pos = maze.idx_to_pos(entrance.node).to_vec2();
+		let straight_distance = |p: &(usize, GridDirection)| {
+			maze.idx_to_pos(p.0).to_vec2().distance(entrance_pos)
+		};
+		// avoid exits right next to the entrance in twisty mazes
+		let min_straight_distance =
+			tweaks.exit_min_distance_ratio * (MAZE_SIZE as f32 * std::f32::consts::SQRT_2);
+		let exit_pair = exit_candidates
+			.iter()
+			.find(|p| straight_distance(p) >= min_straight_distance)
+			.or_else(|| {
+				exit_candidates.iter().max_by(|a, b| {
+					straight_distance(a)
+						.partial_cmp(&straight_distance(b))
+						.unwrap_or(Ordering::Equal)
+				})
+			})
+			.copied()
 			.expect("select exit node");
-		(
-			entrance,
-			SidedNode {
-				node: exit_pair.0,
-				side: exit_pair.1,
-			},
-		)
+		let exit = known_exit.unwrap_or(SidedNode {
+			node: exit_pair.0,
+			side: exit_pair.1,
+		});
+		(entrance, exit)
 	};
 
 	{
@@ -891,19 +1777,49 @@ fn generate_chunk(
 		}
 	}
 
-	let has_block = |pos: IVec2| {
-		pos.x >= 0
-			&& pos.x < CHUNK_SIZE
-			&& pos.y >= 0
-			&& pos.y < CHUNK_SIZE
-			&& grid[pos.y as usize][pos.x as usize]
-	};
+	ChunkLayout {
+		maze,
+		entrance,
+		exit,
+		grid,
+	}
+}
 
-	let quad_mesh: Mesh = Quad::new(Vec2::splat(1.0)).into();
+fn generate_chunk(
+	cmd: &mut Commands,
+	assets: &mut MazeAssets,
+	meshes: &mut Assets<Mesh>,
+	tweaks: &Tweaks,
+	index: usize,
+	coords: ChunkCoords,
+	known_entrance: Option<SidedNode>,
+	known_exit: Option<SidedNode>,
+	occupied_coords: &[ChunkCoords],
+	seed: u64,
+) -> Chunk {
+	let layout = generate_chunk_layout(
+		tweaks,
+		coords,
+		known_entrance,
+		known_exit,
+		occupied_coords,
+		&mut chunk_rng(seed, index),
+	);
+	let has_block = |pos: IVec2| layout.has_block(pos);
+
+	let wall_height = tweaks.ceiling_height;
+	let quad_mesh = {
+		let mut mesh: Mesh = Quad::new(vec2(CELL_SIZE, wall_height)).into();
+		// tile the wall texture vertically instead of stretching it
+		for vertex in mesh.vertices.iter_mut() {
+			vertex.uv.y *= wall_height / CELL_SIZE;
+		}
+		mesh
+	};
 
-	let (chunk_mesh, chunk_walls) = {
+	let (chunk_mesh, wall_colliders) = {
 		let mut chunk_mesh = Mesh::new();
-		let mut chunk_walls = vec![];
+		let mut wall_colliders = WallColliders::default();
 
 		for x in 0..CHUNK_SIZE {
 			for z in 0..CHUNK_SIZE {
@@ -914,53 +1830,50 @@ fn generate_chunk(
 				let cell_transform = GlobalTransform::from_translation(
 					vec3(x as f32, 0., z as f32) + coords.to_world_pos(),
 				);
-				let edges = CollisionEdges {
-					edges: CollisionEdge::ALL
-						.iter()
-						.filter_map(|e| {
-							if !has_block(cell_pos + e.get_direction().to_ivec2()) {
-								Some(e)
-							} else {
-								None
-							}
-						})
-						.copied()
-						.collect(),
-				};
+				let edges = CollisionEdges::exposed(cell_pos, has_block);
 
 				let cell_offset_mat = cell_transform.compute_matrix();
 				for dir in GridDirection::ALL.iter() {
-					if !has_block(cell_pos + dir.get_offset().to_ivec2()) {
+					if !has_block(cell_pos + dir.to_ivec2()) {
+						// the wall rises from the floor, which stays half a cell below the camera
 						let face_transform =
-							dir.get_offset().to_mat4() * Mat4::from_translation(vec3(0., 0., 0.5));
+							Mat4::from_translation(vec3(0., (wall_height - CELL_SIZE) / 2., 0.))
+								* dir.to_mat4()
+								* Mat4::from_translation(vec3(0., 0., 0.5));
 						chunk_mesh
 							.extend_with(quad_mesh.transform(cell_offset_mat * face_transform))
 					}
 				}
 
-				let wall_entity = cmd.spawn_bundle((Wall, cell_transform, edges)).id();
-				chunk_walls.push(wall_entity);
+				let world_cell = cell_pos + coords.0 * CHUNK_SIZE;
+				wall_colliders.0.insert((world_cell.x, world_cell.y), edges);
 			}
 		}
-		(chunk_mesh, chunk_walls)
+		(chunk_mesh, wall_colliders)
 	};
 
 	let chunk = Chunk {
 		index,
 		coords,
-		maze,
-		entrance,
-		exit,
+		maze: layout.maze,
+		entrance: layout.entrance,
+		exit: layout.exit,
 	};
 
 	let wall_color = {
-		let chunk_color: Vec3 = assets.wall_colors[index % assets.wall_colors.len()].into();
 		let wall_tweak_color: Vec3 = Color::rgb_u32(tweaks.wall_material.color).into();
-		chunk_color * wall_tweak_color
+		if tweaks.per_chunk_colors {
+			let chunk_color: Vec3 = assets.wall_colors[index % assets.wall_colors.len()].into();
+			chunk_color * wall_tweak_color
+		} else {
+			wall_tweak_color
+		}
 	};
 
 	let uniforms_from_material = |m: Material| Uniforms {
 		ambient_intensity: tweaks.ambient_light_intensity,
+		ambient_color: Color::rgb_u32(tweaks.ambient_light_color).into(),
+		seam_blend_height: tweaks.floor_seam_blend_height,
 		..Uniforms::from_material(m)
 	};
 
@@ -979,6 +1892,8 @@ fn generate_chunk(
 				assets.wall_tex_diffuse.clone(),
 				assets.wall_tex_normal.clone(),
 			]),
+			NormalMapIntensity(tweaks.wall_material.normal_intensity),
+			wall_colliders,
 			Reset,
 		))
 		.id();
@@ -999,12 +1914,13 @@ fn generate_chunk(
 				assets.floor_tex_normal.clone(),
 			]),
 			uniforms_from_material(tweaks.floor_material),
+			NormalMapIntensity(tweaks.floor_material.normal_intensity),
 		))
 		.insert_bundle(wall_floor_common_components.clone())
 		.id();
 
 	let ceiling_transform = GlobalTransform::from_matrix(
-		Mat4::from_translation(chunk_center + vec3(0., CELL_SIZE / 2., 0.))
+		Mat4::from_translation(chunk_center + vec3(0., wall_height - CELL_SIZE / 2., 0.))
 			* Mat4::from_rotation_z(PI),
 	);
 	let ceiling_entity = cmd
@@ -1015,26 +1931,55 @@ fn generate_chunk(
 				assets.ceiling_tex_normal.clone(),
 			]),
 			uniforms_from_material(tweaks.ceiling_material),
+			NormalMapIntensity(tweaks.ceiling_material.normal_intensity),
 		))
 		.insert_bundle(wall_floor_common_components)
 		.id();
 
-	cmd.entity(chunk_entity).insert(Children(
-		chunk_walls
-			.into_iter()
-			.chain(std::iter::once(floor_entity))
-			.chain(std::iter::once(ceiling_entity))
-			.collect(),
-	));
+	cmd.entity(chunk_entity).insert(Children(vec![floor_entity, ceiling_entity]));
 
 	chunk
 }
 
+/// Collision edges of the wall blocks of a chunk, by world grid cell
+#[derive(Default)]
+struct WallColliders(HashMap<(i32, i32), CollisionEdges>);
+
+impl WallColliders {
+	/// Walls in the nine cells around a position, the only ones close enough to touch the player
+	fn near(&self, pos: Vec3) -> impl Iterator<Item = (Vec3, &CollisionEdges)> + '_ {
+		self.around(pos, 1)
+	}
+
+	/// Walls in the cells up to `radius` cells away from a position on either axis
+	fn around(
+		&self,
+		pos: Vec3,
+		radius: i32,
+	) -> impl Iterator<Item = (Vec3, &CollisionEdges)> + '_ {
+		let (x, z) = world_to_grid(pos);
+		(-radius..=radius)
+			.flat_map(move |dz| (-radius..=radius).map(move |dx| (x + dx, z + dz)))
+			.filter_map(move |cell| self.0.get(&cell).map(|edges| (cell.to_vec3(), edges)))
+	}
+}
+
 struct CollisionEdges {
 	edges: Vec<CollisionEdge>,
 }
 
 impl CollisionEdges {
+	/// Edges of the wall block at `cell_pos` that face open cells
+	fn exposed(cell_pos: IVec2, has_block: impl Fn(IVec2) -> bool) -> Self {
+		Self {
+			edges: CollisionEdge::ALL
+				.iter()
+				.filter(|e| !has_block(cell_pos + e.get_direction().to_ivec2()))
+				.copied()
+				.collect(),
+		}
+	}
+
 	fn get_closest(
 		&self,
 		parent_cell_pos: Vec3,
@@ -1073,6 +2018,11 @@ fn node_to_world(n: &GridNode, c: &Chunk) -> Vec3 {
 	maze_to_grid(c.maze.idx_to_pos(n.idx())).to_vec3() + c.coords.to_world_pos()
 }
 
+fn node_at_world_pos(c: &Chunk, pos: Vec3) -> Option<usize> {
+	let grid_pos = world_to_grid(pos - c.coords.to_world_pos());
+	c.maze.pos_to_idx(grid_to_maze(grid_pos))
+}
+
 fn lerp_angle(p_from: f32, p_to: f32, t: f32) -> f32 {
 	const TAU: f32 = PI * 2.;
 	let difference = (p_to - p_from) % TAU;
@@ -1080,32 +2030,437 @@ fn lerp_angle(p_from: f32, p_to: f32, t: f32) -> f32 {
 	return p_from + distance * t;
 }
 
-impl GridDirection {
-	fn to_rotation(self) -> Quat {
-		Quat::from_rotation_y(Vec2::Y.angle_between(self.get_offset().to_vec2() * vec2(1., -1.)))
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn invalid_clipping_planes_fall_back_to_defaults() {
+		let tweaks = |near_clip, far_clip| Tweaks {
+			near_clip,
+			far_clip,
+			..Default::default()
+		};
+		assert_eq!(clipping_distance(&tweaks(0.5, 60.)), 0.5..60.);
+		let defaults = clipping_distance(&Tweaks::default());
+		for (near, far) in vec![(0., 60.), (-1., 60.), (60., 0.5), (1., 1.)] {
+			assert_eq!(clipping_distance(&tweaks(near, far)), defaults);
+		}
+	}
+
+	#[test]
+	fn chunk_layout_openings() {
+		fn border_openings(layout: &ChunkLayout) -> usize {
+			let last = CHUNK_SIZE - 1;
+			(0..CHUNK_SIZE)
+				.flat_map(|a| vec![ivec2(a, 0), ivec2(a, last), ivec2(0, a), ivec2(last, a)])
+				.filter(|p| !layout.has_block(*p))
+				.count()
+		}
+		fn opening(n: &SidedNode, layout: &ChunkLayout) -> IVec2 {
+			let cell = maze_to_grid(layout.maze.idx_to_pos(n.node)).to_ivec2();
+			cell + n.side.get_offset().to_ivec2()
+		}
+		fn assert_passages_match_links(layout: &ChunkLayout) {
+			let maze = &layout.maze;
+			for node in maze.iter_nodes() {
+				let cell = maze_to_grid(maze.idx_to_pos(node.idx())).to_ivec2();
+				assert!(!layout.has_block(cell));
+				for dir in [GridDirection::Right, GridDirection::Down].iter() {
+					if maze.get_neighbor(node, *dir).is_some() {
+						let passage = cell + dir.get_offset().to_ivec2();
+						assert_eq!(!layout.has_block(passage), maze.has_link(node, *dir));
+					}
+				}
+			}
+		}
+
+		let tweaks = Tweaks::default();
+		let mut rng = SmallRng::seed_from_u64(5);
+		let first = generate_chunk_layout(&tweaks, ChunkCoords::ZERO, None, None, &[], &mut rng);
+		assert_ne!(first.entrance.side, first.exit.side);
+		// the first chunk is entered from the inside
+		assert_eq!(border_openings(&first), 1);
+		assert!(!first.has_block(opening(&first.exit, &first)));
+		assert_passages_match_links(&first);
+
+		let entrance = SidedNode {
+			node: first.maze.get_edge_nodes(GridDirection::Left)[2].idx(),
+			side: GridDirection::Left,
+		};
+		let second = generate_chunk_layout(
+			&tweaks,
+			ChunkCoords(ivec2(1, 0)),
+			Some(entrance),
+			None,
+			&[ChunkCoords::ZERO],
+			&mut rng,
+		);
+		assert_eq!(second.entrance.side, GridDirection::Left);
+		assert_ne!(second.exit.side, GridDirection::Left);
+		assert_eq!(border_openings(&second), 2);
+		assert!(!second.has_block(opening(&second.entrance, &second)));
+		assert!(!second.has_block(opening(&second.exit, &second)));
+		assert_passages_match_links(&second);
+
+		let facing_up = Tweaks {
+			start_facing: Some(GridDirection::Up),
+			..Tweaks::default()
+		};
+		for _ in 0..4 {
+			let layout =
+				generate_chunk_layout(&facing_up, ChunkCoords::ZERO, None, None, &[], &mut rng);
+			assert_eq!(layout.entrance.side, GridDirection::Down);
+		}
 	}
-}
 
-trait TupleVecConversion {
-	fn to_vec2(self) -> Vec2;
-	fn to_vec3(self) -> Vec3;
-	fn to_ivec2(self) -> IVec2;
-	fn to_mat4(self) -> Mat4;
-}
-impl TupleVecConversion for (i32, i32) {
-	fn to_vec2(self) -> Vec2 {
-		Vec2::new(self.0 as f32, self.1 as f32)
+	#[test]
+	fn chunks_derive_from_seed_and_index() {
+		let tweaks = Tweaks::default();
+		let entrance = SidedNode {
+			node: GridMaze::new(MAZE_SIZE, MAZE_SIZE).get_edge_nodes(GridDirection::Left)[3].idx(),
+			side: GridDirection::Left,
+		};
+		let layout = |seed: u64, index: usize| {
+			let layout = generate_chunk_layout(
+				&tweaks,
+				ChunkCoords(ivec2(5, 0)),
+				Some(entrance.clone()),
+				None,
+				&[],
+				&mut chunk_rng(seed, index),
+			);
+			(layout.maze.to_bytes(), layout.exit.node, layout.exit.side)
+		};
+		let chunk = layout(42, 5);
+		// generating other chunks in between doesn't change it
+		layout(42, 4);
+		assert_eq!(chunk, layout(42, 5));
+		assert_ne!(chunk, layout(42, 6));
+		assert_ne!(chunk, layout(43, 5));
+	}
+
+	#[test]
+	fn lookahead_chain_entrances_align() {
+		let tweaks = Tweaks::default();
+		let seed = 7;
+		let to_chunk = |index, coords, layout: ChunkLayout| Chunk {
+			index,
+			coords,
+			maze: layout.maze,
+			entrance: layout.entrance,
+			exit: layout.exit,
+		};
+		let mut rng = chunk_rng(seed, 0);
+		let first = generate_chunk_layout(&tweaks, ChunkCoords::ZERO, None, None, &[], &mut rng);
+		let mut chain = vec![to_chunk(0, ChunkCoords::ZERO, first)];
+		for index in 1..=tweaks.chunk_lookahead * 4 {
+			let (coords, entrance) = next_chunk_placement(chain.last().unwrap());
+			let occupied = chain.iter().map(|c| c.coords).collect::<Vec<_>>();
+			let layout = generate_chunk_layout(
+				&tweaks,
+				coords,
+				Some(entrance),
+				None,
+				&occupied,
+				&mut chunk_rng(seed, index),
+			);
+			chain.push(to_chunk(index, coords, layout));
+		}
+
+		let maze_size = MAZE_SIZE as i32;
+		let cell = |chunk: &Chunk, node: usize| {
+			chunk.coords.0 * maze_size + IVec2::from(chunk.maze.idx_to_pos(node))
+		};
+		for pair in chain.windows(2) {
+			let (previous, next) = (&pair[0], &pair[1]);
+			let exit_dir = IVec2::from(previous.exit.side.get_offset());
+			assert_eq!(next.coords.0, previous.coords.0 + exit_dir);
+			// entered from the edge facing the previous exit, in the cell right past it
+			assert_eq!(next.entrance.side, previous.exit.side.opposite());
+			let edge = next.maze.get_edge_nodes(next.entrance.side);
+			assert!(edge.iter().any(|n| n.idx() == next.entrance.node));
+			assert_eq!(
+				cell(next, next.entrance.node),
+				cell(previous, previous.exit.node) + exit_dir
+			);
+		}
+	}
+
+	#[test]
+	fn auto_walk_heading_is_stable() {
+		use GridDirection::*;
+		#[rustfmt::skip]
+		let maze = &GridMaze::from_ascii(&[
+			"+----+----+----+----+",
+			"|                   |",
+			"+    +----+----+----+",
+			"|                   |",
+			"+----+----+----+----+",
+		].join("\n")).unwrap();
+		let neighbor_position = |node: GridNode| {
+			move |dir: GridDirection| {
+				maze.get_neighbor(&node, dir)
+					.filter(|_| maze.has_link(&node, dir))
+					.map(|n| maze.idx_to_pos(n.idx()).to_vec3())
+			}
+		};
+		let node_at = |pos: Vec3| maze[maze.pos_to_idx((pos.x as i32, pos.z as i32)).unwrap()];
+
+		// deciding again at the same node doesn't change the outcome
+		let start = maze[1];
+		let first = choose_heading(AutoWalkHand::Right, Right, false, neighbor_position(start));
+		for _ in 0..3 {
+			let again = choose_heading(AutoWalkHand::Right, Right, false, neighbor_position(start));
+			assert_eq!(again, first);
+		}
+
+		let mut node = start;
+		let mut heading = Left;
+		let mut headings = vec![];
+		for _ in 0..8 {
+			let (next_heading, next_pos) =
+				choose_heading(AutoWalkHand::Right, heading, false, neighbor_position(node))
+					.expect("walkable direction");
+			headings.push(next_heading);
+			heading = next_heading;
+			node = node_at(next_pos);
+		}
+		// along the top corridor, around the bend and down the lower corridor, reversing once at
+		// its dead end without turning back and forth
+		assert_eq!(headings, vec![Left, Down, Right, Right, Right, Left, Left, Left]);
+	}
+
+	#[test]
+	fn auto_walk_reverse_retraces_path() {
+		#[rustfmt::skip]
+		let maze = &GridMaze::from_ascii(&[
+			"+----+----+----+----+",
+			"|         |         |",
+			"+    +----+    +    +",
+			"|    |         |    |",
+			"+    +    +----+    +",
+			"|              |    |",
+			"+----+----+----+----+",
+		].join("\n")).unwrap();
+		let neighbor_position = |node: GridNode| {
+			move |dir: GridDirection| {
+				maze.get_neighbor(&node, dir)
+					.filter(|_| maze.has_link(&node, dir))
+					.map(|n| maze.idx_to_pos(n.idx()).to_vec3())
+			}
+		};
+		let node_at = |pos: Vec3| maze[maze.pos_to_idx((pos.x as i32, pos.z as i32)).unwrap()];
+		let walk = |hand, mut node: GridNode, mut heading: GridDirection, steps| {
+			let mut nodes = vec![node.idx()];
+			for _ in 0..steps {
+				let (next_heading, next_pos) =
+					choose_heading(hand, heading, false, neighbor_position(node))
+						.expect("walkable direction");
+				heading = next_heading;
+				node = node_at(next_pos);
+				nodes.push(node.idx());
+			}
+			(nodes, heading)
+		};
+
+		// through dead ends on the way, then turned around during the last step
+		let (forward, heading) = walk(AutoWalkHand::Right, maze[0], GridDirection::Right, 14);
+		let step_start = maze[forward[forward.len() - 2]];
+		let (back, _) = walk(AutoWalkHand::Left, step_start, heading.opposite(), 13);
+		let mut expected = forward[..forward.len() - 1].to_vec();
+		expected.reverse();
+		assert_eq!(back, expected);
+	}
+
+	#[test]
+	fn auto_walk_solver_takes_shortest_path() {
+		use GridDirection::*;
+		#[rustfmt::skip]
+		let maze = &GridMaze::from_ascii(&[
+			"+----+----+----+----+",
+			"|         |         |",
+			"+    +    +    +----+",
+			"|    |              |",
+			"+----+----+----+----+",
+		].join("\n")).unwrap();
+		let neighbor_position = |node: GridNode| {
+			move |dir: GridDirection| {
+				maze.get_neighbor(&node, dir)
+					.filter(|_| maze.has_link(&node, dir))
+					.map(|n| maze.idx_to_pos(n.idx()).to_vec3())
+			}
+		};
+		let node_at = |pos: Vec3| maze[maze.pos_to_idx((pos.x as i32, pos.z as i32)).unwrap()];
+		let exit = SidedNode {
+			node: 3,
+			side: Right,
+		};
+		let distances = maze.distances(&maze[exit.node]);
+
+		let mut node = maze[4];
+		let mut headings = vec![];
+		while node.idx() != exit.node && headings.len() < 8 {
+			let (heading, pos) =
+				choose_solving_heading(maze, &node, &exit, &distances, neighbor_position(node))
+					.expect("walkable direction");
+			headings.push(heading);
+			node = node_at(pos);
+		}
+		// around the wall in the middle, passing the dead end in the lower right corner
+		assert_eq!(headings, vec![Up, Right, Down, Right, Up, Right]);
+
+		// leaves through the exit side once the next chunk is there to walk to
+		let next_entrance = vec3(5., 0., 0.);
+		let exit_heading = |next_chunk: Option<Vec3>| {
+			choose_solving_heading(maze, &node, &exit, &distances, |dir| {
+				if dir == exit.side {
+					next_chunk
+				} else {
+					neighbor_position(node)(dir)
+				}
+			})
+		};
+		assert_eq!(exit_heading(Some(next_entrance)), Some((Right, next_entrance)));
+		assert_eq!(exit_heading(None), None);
 	}
 
-	fn to_vec3(self) -> Vec3 {
-		Vec3::new(self.0 as f32, 0., self.1 as f32)
+	#[test]
+	fn auto_walk_turns_take_fixed_time() {
+		let tweaks = Tweaks::default();
+		let delta = 1. / 64.;
+		let turn_steps = (tweaks.auto_walk_turn_duration / delta).ceil() as usize;
+		// a step within a chunk, and a longer one into the next chunk
+		for &length in &[1., 3.] {
+			let mut state = AutoWalkState::default();
+			state.start_step((Vec3::ZERO, vec3(length, 0., 0.)), (0., PI / 2.));
+			let mut yaws = vec![];
+			while let Some((_, yaw)) = state.advance(delta, &tweaks) {
+				yaws.push(yaw);
+			}
+			assert!(yaws.len() >= turn_steps);
+			assert!((yaws[turn_steps - 2] - PI / 2.).abs() > 0.0001);
+			assert!((yaws[turn_steps - 1] - PI / 2.).abs() < 0.0001);
+		}
 	}
 
-	fn to_ivec2(self) -> IVec2 {
-		IVec2::new(self.0, self.1)
+	#[test]
+	fn auto_walk_hands_are_mirrored() {
+		use GridDirection::*;
+		#[rustfmt::skip]
+		let maze = &GridMaze::from_ascii(&[
+			"+----+----+----+",
+			"|              |",
+			"+    +    +    +",
+			"|    |    |    |",
+			"+----+----+----+",
+		].join("\n")).unwrap();
+		let junction = maze[1];
+		let neighbor_position = |dir: GridDirection| {
+			maze.get_neighbor(&junction, dir)
+				.filter(|_| maze.has_link(&junction, dir))
+				.map(|n| maze.idx_to_pos(n.idx()).to_vec3())
+		};
+		let first_move = |hand| choose_heading(hand, Up, false, neighbor_position);
+
+		// arriving at the T junction from below, each hand takes the branch on its own side
+		let (right_dir, right_pos) = first_move(AutoWalkHand::Right).unwrap();
+		let (left_dir, left_pos) = first_move(AutoWalkHand::Left).unwrap();
+		assert_eq!((right_dir, left_dir), (Right, Left));
+		let junction_pos = maze.idx_to_pos(junction.idx()).to_vec3();
+		assert_eq!(right_pos - junction_pos, -(left_pos - junction_pos));
 	}
 
-	fn to_mat4(self) -> Mat4 {
-		Mat4::look_at_rh(Vec3::ZERO, self.to_vec3() * vec3(1., 1., -1.), Vec3::Y)
+	#[test]
+	fn nearby_wall_collisions_match_brute_force() {
+		#[rustfmt::skip]
+		let layout = [
+			"#######",
+			"#     #",
+			"# ### #",
+			"# #   #",
+			"# # # #",
+			"#   #  ",
+			"#######",
+		];
+		let has_block = |p: IVec2| {
+			layout
+				.get(p.y as usize)
+				.and_then(|row| row.as_bytes().get(p.x as usize))
+				.map_or(false, |c| *c == b'#')
+		};
+		let mut colliders = WallColliders::default();
+		for z in 0..layout.len() as i32 {
+			for x in 0..layout[0].len() as i32 {
+				if has_block(ivec2(x, z)) {
+					colliders.0.insert((x, z), CollisionEdges::exposed(ivec2(x, z), has_block));
+				}
+			}
+		}
+		// in the same row-major order as the nearby cells
+		let mut all_walls = colliders.0.iter().collect::<Vec<_>>();
+		all_walls.sort_by_key(|((x, z), _)| (*z, *x));
+
+		for step_z in 0..=60 {
+			for step_x in 0..=60 {
+				let pos = vec3(step_x as f32 * 0.1, 0., step_z as f32 * 0.1);
+				if has_block(ivec2(pos.x.round() as i32, pos.z.round() as i32)) {
+					continue;
+				}
+				let brute_force = resolve_wall_collisions(
+					pos,
+					0.2,
+					all_walls.iter().map(|(cell, edges)| (cell.to_vec3(), *edges)),
+				);
+				let nearby = resolve_wall_collisions(pos, 0.2, colliders.near(pos));
+				assert_eq!(brute_force, nearby, "player at {}", pos);
+			}
+		}
+	}
+
+	#[test]
+	fn wall_hit_shakes_once_per_contact() {
+		let delta = 1. / 20.;
+		let mut touching = false;
+		let mut shakes = 0;
+		let mut frame = |correction| {
+			if wall_hit_shake(&mut touching, correction, delta).is_some() {
+				shakes += 1;
+			}
+		};
+		// held against the wall at walking speed, on a slow frame rate
+		for _ in 0..10 {
+			frame(Some(3. * delta));
+		}
+		frame(None);
+		frame(Some(3. * delta));
+		assert_eq!(shakes, 2);
+	}
+
+	#[test]
+	fn wall_clip_keeps_player_outside() {
+		let has_block = |p: IVec2| p == IVec2::ZERO;
+		let edges = CollisionEdges::exposed(IVec2::ZERO, has_block);
+		for radius in vec![0.1, 0.3, 0.45] {
+			let touching = CELL_SIZE / 2. + radius;
+			for pos in vec![
+				vec3(touching - 0.05, 0., 0.1),
+				vec3(-touching + 0.02, 0., -0.2),
+				vec3(0.15, 0., touching - 0.08),
+				vec3(-0.3, 0., -touching + 0.01),
+			] {
+				let wall = std::iter::once((Vec3::ZERO, &edges));
+				let resolved =
+					resolve_wall_collisions(pos, radius, wall).expect("overlapping the wall");
+				let distance = resolved.x.abs().max(resolved.z.abs());
+				assert!(distance >= touching - 1e-5, "radius {} at {}: {}", radius, pos, resolved);
+				// only pushed out through the face it's closest to, sliding along the other axis
+				if pos.x.abs() > pos.z.abs() {
+					assert_eq!(resolved.z, pos.z);
+				} else {
+					assert_eq!(resolved.x, pos.x);
+				}
+			}
+		}
 	}
 }
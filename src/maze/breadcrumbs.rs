@@ -0,0 +1,60 @@
+use super::{
+	grid_to_maze, maze_to_grid, solid_color::SolidColorAssets, Chunk, CurrentChunk, Reset,
+	TupleVecConversion,
+};
+use crate::maze_gen::world_to_grid;
+use crate::key_bindings::{Action, KeyBindings};
+use crate::prelude::*;
+use glam::vec3;
+
+const MARKER_SIZE: f32 = 0.15;
+const MARKER_HEIGHT: f32 = -0.35;
+
+pub struct BreadcrumbAssets {
+	mesh: Handle<Mesh>,
+}
+
+/// Marker dropped by the player to help with backtracking
+pub struct Breadcrumb;
+
+pub fn load_breadcrumb_assets(mut cmd: Commands, mut meshes: ResMut<Assets<Mesh>>) {
+	let mesh = meshes.add(Cube::new(MARKER_SIZE).into());
+	cmd.insert_resource(BreadcrumbAssets { mesh });
+}
+
+pub fn drop_breadcrumb(
+	mut cmd: Commands,
+	input: Res<Keyboard>,
+	bindings: Res<KeyBindings>,
+	assets: Res<BreadcrumbAssets>,
+	solid_color: Res<SolidColorAssets>,
+	current_chunk: Res<CurrentChunk>,
+	q_cam: Query<&GlobalTransform, With<Camera>>,
+	mut q_chunks: Query<(&Chunk, &mut Children)>,
+) {
+	if !bindings.was_just_pressed(Action::DropBreadcrumb, &input) {
+		return;
+	}
+	let (chunk, mut chunk_children) =
+		match current_chunk.0.and_then(|e| q_chunks.get_mut(e).ok()) {
+			Some(found) => found,
+			None => return,
+		};
+	let cam_pos = q_cam.single().unwrap().translation - chunk.coords.to_world_pos();
+	let cam_grid_pos = world_to_grid(cam_pos);
+	// snap to the center of the maze cell the player is standing in
+	let cell_center = maze_to_grid(grid_to_maze(cam_grid_pos)).to_vec3();
+	let transform = GlobalTransform::from_translation(
+		cell_center + chunk.coords.to_world_pos() + vec3(0., MARKER_HEIGHT, 0.),
+	);
+	let marker = cmd
+		.spawn_bundle(solid_color.bundle(
+			assets.mesh.clone(),
+			vec3(1.0, 0.8, 0.3),
+			transform.compute_matrix(),
+		))
+		.insert_bundle((Breadcrumb, transform, Reset))
+		.id();
+	// despawned together with the chunk
+	chunk_children.0.push(marker);
+}
@@ -0,0 +1,129 @@
+use crate::prelude::*;
+use easer::functions::{Easing, Quad as QuadEase};
+use glam::{vec3, EulerRot, Quat, Vec3};
+use miniquad::date;
+use rand::{
+	prelude::{Rng, SmallRng},
+	SeedableRng,
+};
+
+/// Camera offset at full intensity, in cells
+const MAX_OFFSET: f32 = 0.04;
+/// Camera rotation around each axis at full intensity, in degrees
+const MAX_ANGLE: f32 = 1.5;
+
+/// Shakes the camera, `intensity` from 0 to 1 dying down over `duration` seconds. A shake
+/// weaker than the one in progress is ignored.
+pub struct CameraShake {
+	pub intensity: f32,
+	pub duration: f32,
+}
+
+/// Shake in progress, and the offset applied to the camera this frame
+pub struct CameraShakeState {
+	intensity: f32,
+	duration: f32,
+	elapsed: f32,
+	translation: Vec3,
+	rotation: Quat,
+	rng: SmallRng,
+}
+
+impl Default for CameraShakeState {
+	fn default() -> Self {
+		Self {
+			intensity: 0.,
+			duration: 0.,
+			elapsed: 0.,
+			translation: Vec3::ZERO,
+			rotation: Quat::IDENTITY,
+			// not drawn from `Random`, so shaking doesn't change the mazes generated from a seed
+			rng: SmallRng::seed_from_u64(date::now() as u64),
+		}
+	}
+}
+
+impl CameraShakeState {
+	/// Part of the shake intensity left, easing out towards the end
+	fn strength(&self) -> f32 {
+		if self.elapsed >= self.duration {
+			return 0.;
+		}
+		let remaining = 1. - self.elapsed / self.duration;
+		self.intensity * QuadEase::ease_in(remaining, 0., 1., 1.)
+	}
+}
+
+/// Runs before the systems moving the camera, so they see it without last frame's shake
+pub fn remove_camera_shake(
+	mut shake: ResMut<CameraShakeState>,
+	mut q_cam: Query<&mut GlobalTransform, With<Camera>>,
+) {
+	if shake.translation == Vec3::ZERO && shake.rotation == Quat::IDENTITY {
+		return;
+	}
+	let mut transform = q_cam.single_mut().unwrap();
+	transform.translation -= shake.translation;
+	transform.rotation *= shake.rotation.inverse();
+	shake.translation = Vec3::ZERO;
+	shake.rotation = Quat::IDENTITY;
+}
+
+/// Runs after the systems moving the camera, the offset is taken back out next frame
+pub fn apply_camera_shake(
+	mut shake: ResMut<CameraShakeState>,
+	mut shake_events: EventReader<CameraShake>,
+	time: Res<Time>,
+	mut q_cam: Query<&mut GlobalTransform, With<Camera>>,
+) {
+	shake.elapsed += time.delta_seconds();
+	for event in shake_events.iter() {
+		if event.intensity > shake.strength() && event.duration > 0. {
+			shake.intensity = event.intensity.min(1.);
+			shake.duration = event.duration;
+			shake.elapsed = 0.;
+		}
+	}
+	let strength = shake.strength();
+	if strength <= 0. {
+		return;
+	}
+	let mut random = || shake.rng.gen_range(-1.0..=1.0) * strength;
+	let translation = vec3(random(), random(), random()) * MAX_OFFSET;
+	let max_angle = MAX_ANGLE.to_radians();
+	let rotation = Quat::from_euler(
+		EulerRot::YXZ,
+		random() * max_angle,
+		random() * max_angle,
+		random() * max_angle,
+	);
+	let mut transform = q_cam.single_mut().unwrap();
+	transform.translation += translation;
+	transform.rotation *= rotation;
+	shake.translation = translation;
+	shake.rotation = rotation;
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn shake_dies_down() {
+		let mut shake = CameraShakeState {
+			intensity: 0.8,
+			duration: 0.5,
+			..Default::default()
+		};
+		assert_eq!(shake.strength(), 0.8);
+		let mut previous = shake.strength();
+		for _ in 0..4 {
+			shake.elapsed += 0.1;
+			assert!(shake.strength() < previous);
+			previous = shake.strength();
+		}
+		shake.elapsed = 0.5;
+		assert_eq!(shake.strength(), 0.);
+		assert_eq!(CameraShakeState::default().strength(), 0.);
+	}
+}
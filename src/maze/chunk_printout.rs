@@ -0,0 +1,42 @@
+use super::{Chunk, CurrentChunk};
+use crate::maze_gen::{overlay_distances, Distances};
+use crate::prelude::*;
+use miniquad::KeyCode;
+
+/// Logs the current chunk as ASCII art with F8, marking the path from the entrance to the exit
+/// with its running cost. Shift+F8 shows the distance to every cell from the entrance instead.
+pub fn print_current_chunk(
+	input: Res<Keyboard>,
+	current_chunk: Res<CurrentChunk>,
+	q_chunks: Query<&Chunk>,
+) {
+	if !input.was_just_pressed(KeyCode::F8) {
+		return;
+	}
+	let chunk = match current_chunk.0.and_then(|e| q_chunks.get(e).ok()) {
+		Some(chunk) => chunk,
+		None => return,
+	};
+	let maze = &chunk.maze;
+	let entrance = maze[chunk.entrance.node];
+	let exit = maze[chunk.exit.node];
+	let from_entrance = maze.distances(&entrance);
+	let art = if input.is_pressed(KeyCode::LeftShift) || input.is_pressed(KeyCode::RightShift) {
+		overlay_distances(maze, &from_entrance)
+	} else {
+		let mut path = Distances::new(entrance);
+		for node in maze.path_to(&entrance, &exit) {
+			path.insert(node, from_entrance[node]);
+		}
+		maze.display_path(&path)
+	};
+	info!(
+		"Chunk {}, entrance {} ({:?}), exit {} ({:?})\n{}",
+		chunk.index,
+		chunk.entrance.node,
+		chunk.entrance.side,
+		chunk.exit.node,
+		chunk.exit.side,
+		art
+	);
+}
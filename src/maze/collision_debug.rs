@@ -0,0 +1,116 @@
+use super::{
+	solid_color::{SolidColorAssets, SolidColorUniforms},
+	player_radius, tweaks::Tweaks, Reset, WallColliders, CELL_SIZE,
+};
+use crate::prelude::*;
+use glam::{vec2, vec3, Vec2, Vec3};
+use miniquad::KeyCode;
+
+/// Walls within this many cells of the player get their collision edges drawn
+const DEBUG_RADIUS: i32 = 4;
+const LINE_WIDTH: f32 = 0.02;
+// just above the floor, which sits half a cell below the camera
+const LINE_HEIGHT: f32 = -CELL_SIZE / 2. + 0.01;
+
+/// Outlines of the collision shapes, rebuilt every frame into the same mesh while shown
+pub enum CollisionDebugLayer {
+	Player,
+	WallEdges,
+}
+
+pub fn toggle_collision_debug(
+	mut cmd: Commands,
+	input: Res<Keyboard>,
+	solid_color: Res<SolidColorAssets>,
+	q: Query<Entity, With<CollisionDebugLayer>>,
+) {
+	if !input.was_just_pressed(KeyCode::F4) {
+		return;
+	}
+	if q.iter().next().is_some() {
+		for entity in q.iter() {
+			cmd.entity(entity).despawn();
+		}
+		return;
+	}
+	for (layer, color) in vec![
+		(CollisionDebugLayer::Player, vec3(0.2, 1.0, 0.2)),
+		(CollisionDebugLayer::WallEdges, vec3(1.0, 0.2, 0.2)),
+	] {
+		cmd.spawn_bundle((
+			layer,
+			SolidColorUniforms {
+				color,
+				..Default::default()
+			},
+			solid_color.shader.clone(),
+			Reset,
+		));
+	}
+}
+
+pub fn update_collision_debug(
+	mut cmd: Commands,
+	mut meshes: ResMut<Assets<Mesh>>,
+	q_layers: Query<(Entity, &CollisionDebugLayer, Option<&Handle<Mesh>>)>,
+	q_walls: Query<&WallColliders>,
+	q_cam: Query<&GlobalTransform, With<Camera>>,
+	tweaks: Res<Tweaks>,
+) {
+	let player_pos = q_cam.single().unwrap().translation;
+	for (entity, layer, layer_mesh) in q_layers.iter() {
+		let mut mesh = Mesh::new();
+		match layer {
+			CollisionDebugLayer::Player => {
+				let center = vec2(player_pos.x, player_pos.z);
+				let radius = player_radius(&tweaks);
+				let corners = [(-1., -1.), (1., -1.), (1., 1.), (-1., 1.)]
+					.iter()
+					.map(|(x, z)| center + vec2(*x, *z) * radius)
+					.collect::<Vec<_>>();
+				for i in 0..corners.len() {
+					mesh.extend_with(line_mesh(corners[i], corners[(i + 1) % corners.len()]));
+				}
+			}
+			CollisionDebugLayer::WallEdges => {
+				let walls = q_walls.iter().flat_map(|w| w.around(player_pos, DEBUG_RADIUS));
+				for (wall_pos, edges) in walls {
+					for edge in edges.edges.iter() {
+						let face_center = wall_pos + edge.get_offset(0.);
+						// the face runs perpendicular to its outward direction
+						let (dx, dz) = edge.get_direction();
+						let along = vec2(dz as f32, dx as f32) * (CELL_SIZE / 2.);
+						let center = vec2(face_center.x, face_center.z);
+						mesh.extend_with(line_mesh(center - along, center + along));
+					}
+				}
+			}
+		}
+		match layer_mesh.and_then(|handle| meshes.get_mut(handle)) {
+			Some(layer_mesh) => *layer_mesh = mesh,
+			None => {
+				cmd.entity(entity).insert(meshes.add(mesh));
+			}
+		}
+	}
+}
+
+/// A thin flat quad on the XZ plane, visible from above and below
+fn line_mesh(from: Vec2, to: Vec2) -> Mesh {
+	let side = (to - from).perp().normalize_or_zero() * (LINE_WIDTH / 2.);
+	let vertex = |p: Vec2| Vertex {
+		pos: vec3(p.x, LINE_HEIGHT, p.y),
+		normal: Vec3::Y,
+		uv: Vec2::ZERO,
+		color: Vertex::WHITE,
+	};
+	Mesh {
+		vertices: vec![
+			vertex(from - side),
+			vertex(to - side),
+			vertex(to + side),
+			vertex(from + side),
+		],
+		indices: vec![0, 1, 2, 0, 2, 3, 0, 2, 1, 0, 3, 2],
+	}
+}
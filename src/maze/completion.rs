@@ -0,0 +1,74 @@
+use super::{
+	node_at_world_pos,
+	run_stats::{format_run_time, RunCompleted, RunStats},
+	Chunk, ChunkEntered, CurrentChunk, Reset, Tweaks,
+};
+use crate::prelude::*;
+use glam::vec2;
+use miniquad::KeyCode;
+
+/// Shown once the exit of the last chunk of a finite maze is reached
+pub struct CompletionOverlay;
+
+pub fn detect_maze_completion(
+	mut state: ResMut<State>,
+	mut entered_event: EventReader<ChunkEntered>,
+	mut completed_event: EventWriter<RunCompleted>,
+	stats: Res<RunStats>,
+	mut in_final_chunk: Local<bool>,
+	tweaks: Res<Tweaks>,
+	current_chunk: Res<CurrentChunk>,
+	q_chunks: Query<&Chunk>,
+	q_cam: Query<&GlobalTransform, With<Camera>>,
+) {
+	let final_index = match tweaks.maze_length {
+		Some(length) => length.saturating_sub(1),
+		None => return,
+	};
+	for ChunkEntered(entered) in entered_event.iter() {
+		*in_final_chunk = q_chunks
+			.get(*entered)
+			.map(|c| c.index == final_index)
+			.unwrap_or_default();
+	}
+	if !*in_final_chunk {
+		return;
+	}
+	if let Some(chunk) = current_chunk.0.and_then(|e| q_chunks.get(e).ok()) {
+		let cam_pos = q_cam.single().unwrap().translation;
+		if node_at_world_pos(chunk, cam_pos) == Some(chunk.exit.node) {
+			*in_final_chunk = false;
+			state.schedule_transition(AppState::Complete);
+			completed_event.send(RunCompleted(stats.clone()));
+		}
+	}
+}
+
+pub fn spawn_completion_overlay(
+	mut cmd: Commands,
+	window: Res<WindowSize>,
+	mut capture: ResMut<MouseCapture>,
+	stats: Res<RunStats>,
+) {
+	capture.requested = false;
+	let scale = 4.;
+	cmd.spawn_bundle((
+		CompletionOverlay,
+		Text {
+			value: format!(
+				"MAZE COMPLETE!\n{}\nPRESS ENTER TO PLAY AGAIN",
+				format_run_time(stats.elapsed_seconds)
+			),
+			position: vec2(window.width / 2. - 48. * scale, window.height / 2. - 10. * scale),
+			scale,
+			color: Color::rgb(0.3, 1., 0.3),
+		},
+		Reset,
+	));
+}
+
+pub fn read_restart_input(mut state: ResMut<State>, input: Res<Keyboard>) {
+	if input.was_just_pressed(KeyCode::Enter) || input.was_just_pressed(KeyCode::KpEnter) {
+		state.schedule_transition(AppState::Play);
+	}
+}
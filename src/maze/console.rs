@@ -0,0 +1,309 @@
+use super::{seed::RequestedSeed, tweaks::Tweaks, Random};
+use crate::prelude::*;
+use glam::vec2;
+use miniquad::KeyCode;
+use TweakValue::{Bool, F32, Usize};
+
+const TEXT_SCALE: f32 = 2.;
+const MARGIN: f32 = 8.;
+/// Output lines kept above the input line
+const LOG_LINES: usize = 8;
+
+/// Drop-down console toggled with the backtick key, for changing tweaks while playing.
+/// Takes over the keyboard while open.
+#[derive(Default)]
+pub struct DevConsole {
+	/// Line being typed, while the console is open
+	input: Option<String>,
+	log: Vec<String>,
+}
+
+pub struct ConsoleDisplay;
+
+/// Commands and their arguments, listed by `help`
+const COMMANDS: &[(&str, &str)] = &[
+	("help", "list commands"),
+	("tweaks [prefix]", "list tweaks that can be changed"),
+	("get <tweak>", "show the value of a tweak"),
+	("set <tweak> <value>", "change a tweak, some regenerate the maze"),
+	("restart", "regenerate the maze with the same seed"),
+	("seed <seed>", "generate a new maze"),
+];
+
+/// Tweaks that can be changed from the console, whether the maze is generated again to apply a
+/// change, and the tweak's value. Names can be shortened to any unique prefix.
+const TWEAKS: &[ConsoleTweak] = &[
+	("ambient_light_intensity", true, |t| F32(&mut t.ambient_light_intensity)),
+	("wall_material.normal_intensity", true, |t| F32(&mut t.wall_material.normal_intensity)),
+	("wall_material.specular_strength", true, |t| F32(&mut t.wall_material.specular_strength)),
+	("wall_material.shininess", true, |t| F32(&mut t.wall_material.shininess)),
+	("per_chunk_colors", true, |t| Bool(&mut t.per_chunk_colors)),
+	("floor_material.normal_intensity", true, |t| F32(&mut t.floor_material.normal_intensity)),
+	("floor_material.specular_strength", true, |t| F32(&mut t.floor_material.specular_strength)),
+	("floor_material.shininess", true, |t| F32(&mut t.floor_material.shininess)),
+	("ceiling_material.normal_intensity", true, |t| F32(&mut t.ceiling_material.normal_intensity)),
+	("ceiling_material.specular_strength", true, |t| {
+		F32(&mut t.ceiling_material.specular_strength)
+	}),
+	("ceiling_material.shininess", true, |t| F32(&mut t.ceiling_material.shininess)),
+	("ceiling_height", true, |t| F32(&mut t.ceiling_height)),
+	("floor_seam_blend_height", true, |t| F32(&mut t.floor_seam_blend_height)),
+	("mouse_sensitivity", false, |t| F32(&mut t.mouse_sensitivity)),
+	("mouse_smoothing", false, |t| F32(&mut t.mouse_smoothing)),
+	("invert_look_x", false, |t| Bool(&mut t.invert_look_x)),
+	("invert_look_y", false, |t| Bool(&mut t.invert_look_y)),
+	("player_radius", false, |t| F32(&mut t.player_radius)),
+	("eye_height", false, |t| F32(&mut t.eye_height)),
+	("chunk_keep_distance", false, |t| F32(&mut t.chunk_keep_distance)),
+	("chunk_fade_duration", false, |t| F32(&mut t.chunk_fade_duration)),
+	("chunk_lookahead", false, |t| Usize(&mut t.chunk_lookahead)),
+	("render_distance", false, |t| Usize(&mut t.render_distance)),
+	("near_clip", true, |t| F32(&mut t.near_clip)),
+	("far_clip", true, |t| F32(&mut t.far_clip)),
+	("exit_min_distance_ratio", false, |t| F32(&mut t.exit_min_distance_ratio)),
+	("difficult_region_count", false, |t| Usize(&mut t.difficult_region_count)),
+	("footstep_interval", false, |t| F32(&mut t.footstep_interval)),
+	("auto_walk_speed", false, |t| F32(&mut t.auto_walk_speed)),
+	("auto_walk_turn_duration", false, |t| F32(&mut t.auto_walk_turn_duration)),
+];
+
+type ConsoleTweak = (&'static str, bool, fn(&mut Tweaks) -> TweakValue<'_>);
+
+enum TweakValue<'a> {
+	F32(&'a mut f32),
+	Usize(&'a mut usize),
+	Bool(&'a mut bool),
+}
+
+impl TweakValue<'_> {
+	fn set(&mut self, value: &str) -> Result<(), String> {
+		let invalid = |e: &dyn std::fmt::Display| format!("invalid value {}: {}", value, e);
+		match self {
+			TweakValue::F32(v) => **v = value.parse().map_err(|e| invalid(&e))?,
+			TweakValue::Usize(v) => **v = value.parse().map_err(|e| invalid(&e))?,
+			TweakValue::Bool(v) => **v = value.parse().map_err(|e| invalid(&e))?,
+		}
+		Ok(())
+	}
+
+	fn display(&self) -> String {
+		match self {
+			TweakValue::F32(v) => v.to_string(),
+			TweakValue::Usize(v) => v.to_string(),
+			TweakValue::Bool(v) => v.to_string(),
+		}
+	}
+}
+
+/// The tweak named exactly, or the only one starting with `prefix`
+fn find_tweak(prefix: &str) -> Result<ConsoleTweak, String> {
+	if let Some(exact) = TWEAKS.iter().find(|(name, ..)| *name == prefix) {
+		return Ok(*exact);
+	}
+	let matches = TWEAKS
+		.iter()
+		.filter(|(name, ..)| name.starts_with(prefix))
+		.collect::<Vec<_>>();
+	match matches.as_slice() {
+		[only] => Ok(**only),
+		[] => Err(format!("unknown tweak {}", prefix)),
+		_ => Err(format!("{} matches {} tweaks", prefix, matches.len())),
+	}
+}
+
+/// Runs a console line. Returns the output, and the seed to generate the maze with if it needs
+/// to be generated again.
+fn run_command(
+	line: &str,
+	tweaks: &mut Tweaks,
+	current_seed: u64,
+) -> Result<(String, Option<u64>), String> {
+	let words = line.split_whitespace().collect::<Vec<_>>();
+	match words.as_slice() {
+		[] => Ok((String::new(), None)),
+		["help"] => {
+			let lines = COMMANDS
+				.iter()
+				.map(|(usage, description)| format!("{} - {}", usage, description));
+			Ok((lines.collect::<Vec<_>>().join("\n"), None))
+		}
+		["tweaks"] => Ok((list_tweaks(""), None)),
+		["tweaks", prefix] => Ok((list_tweaks(prefix), None)),
+		["get", name] => {
+			let (name, _, tweak) = find_tweak(name)?;
+			let value = tweak(tweaks).display();
+			Ok((format!("{} = {}", name, value), None))
+		}
+		["set", name, value] => {
+			let (name, regenerate, tweak) = find_tweak(name)?;
+			let mut tweak = tweak(tweaks);
+			tweak.set(value)?;
+			let output = format!("{} = {}", name, tweak.display());
+			Ok((output, regenerate.then(|| current_seed)))
+		}
+		["restart"] => Ok((String::new(), Some(current_seed))),
+		["seed", seed] => {
+			let seed = seed.parse().map_err(|e| format!("invalid seed {}: {}", seed, e))?;
+			Ok((String::new(), Some(seed)))
+		}
+		[command, ..] => Err(format!("unknown command {}, see help", command)),
+	}
+}
+
+fn list_tweaks(prefix: &str) -> String {
+	let names = TWEAKS
+		.iter()
+		.map(|(name, ..)| *name)
+		.filter(|name| name.starts_with(prefix));
+	names.collect::<Vec<_>>().join("\n")
+}
+
+/// Runs first in the frame, so the keys typed into the open console don't reach other systems
+pub fn read_console_input(
+	mut console: ResMut<DevConsole>,
+	mut input: ResMut<Keyboard>,
+	mut text_input: EventReader<TextInput>,
+	mut tweaks: ResMut<Tweaks>,
+	mut requested_seed: ResMut<RequestedSeed>,
+	mut state: ResMut<State>,
+	random: Res<Random>,
+) {
+	let typed = text_input
+		.iter()
+		.map(|t| t.character)
+		.filter(|c| *c != '`' && !c.is_control())
+		.collect::<String>();
+	let toggled = input.was_just_pressed(KeyCode::GraveAccent);
+	if console.input.is_none() {
+		if toggled {
+			console.input = Some(String::new());
+			input.clear();
+		}
+		return;
+	}
+	let submitted =
+		input.was_just_pressed(KeyCode::Enter) || input.was_just_pressed(KeyCode::KpEnter);
+	let erased = input.was_just_pressed(KeyCode::Backspace);
+	input.clear();
+	if toggled {
+		console.input = None;
+		return;
+	}
+	// only touched on input, as changes rebuild the display
+	if !erased && !submitted && typed.is_empty() {
+		return;
+	}
+	let line = console.input.as_mut().unwrap();
+	if erased {
+		line.pop();
+	}
+	line.push_str(&typed);
+	if !submitted {
+		return;
+	}
+
+	let line = console.input.replace(String::new()).unwrap();
+	console.log.push(format!("> {}", line));
+	match run_command(&line.to_lowercase(), &mut tweaks, random.seed) {
+		Ok((output, regenerate)) => {
+			console.log.extend(output.lines().map(String::from));
+			if let Some(seed) = regenerate {
+				requested_seed.0 = Some(seed);
+				state.schedule_transition(AppState::Play);
+			}
+		}
+		Err(e) => console.log.push(e),
+	}
+	let excess = console.log.len().saturating_sub(LOG_LINES);
+	console.log.drain(..excess);
+}
+
+pub fn update_console_display(
+	mut cmd: Commands,
+	console: Res<DevConsole>,
+	window: Res<WindowSize>,
+	q: Query<Entity, With<ConsoleDisplay>>,
+) {
+	if !console.is_changed() {
+		return;
+	}
+	for entity in q.iter() {
+		cmd.entity(entity).despawn();
+	}
+	let line = match console.input {
+		Some(ref line) => line,
+		None => return,
+	};
+	let value = console
+		.log
+		.iter()
+		.cloned()
+		.chain(std::iter::once(format!("> {}_", line)))
+		.collect::<Vec<_>>()
+		.join("\n");
+	// glyphs are 5 pixels tall with 2 pixels between lines at scale 1
+	let height = (LOG_LINES + 1) as f32 * 7. * TEXT_SCALE + MARGIN * 2.;
+	cmd.spawn_bundle((
+		ConsoleDisplay,
+		ScreenQuad {
+			color: Color::rgba(0., 0., 0., 0.75),
+			..ScreenQuad::new(Rect {
+				left: 0.,
+				right: window.width,
+				top: 0.,
+				bottom: height,
+			})
+		},
+	));
+	cmd.spawn_bundle((
+		ConsoleDisplay,
+		Text {
+			value,
+			position: vec2(MARGIN, MARGIN),
+			scale: TEXT_SCALE,
+			color: Color::rgb(0.8, 1., 0.8),
+		},
+	));
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn console_commands() {
+		let mut tweaks = Tweaks::default();
+		let seed = 42;
+		assert_eq!(
+			run_command("set ambient 0.3", &mut tweaks, seed),
+			Ok(("ambient_light_intensity = 0.3".to_string(), Some(seed)))
+		);
+		assert_eq!(tweaks.ambient_light_intensity, 0.3);
+		assert_eq!(
+			run_command("set invert_look_y true", &mut tweaks, seed),
+			Ok(("invert_look_y = true".to_string(), None))
+		);
+		assert!(tweaks.invert_look_y);
+		assert_eq!(
+			run_command("get render", &mut tweaks, seed),
+			Ok(("render_distance = 2".to_string(), None))
+		);
+		assert_eq!(run_command("seed 7", &mut tweaks, seed), Ok((String::new(), Some(7))));
+		// ambiguous prefix, bad value and unknown command
+		assert!(run_command("set invert true", &mut tweaks, seed).is_err());
+		assert!(run_command("set render_distance far", &mut tweaks, seed).is_err());
+		assert!(run_command("teleport", &mut tweaks, seed).is_err());
+		assert_eq!(tweaks.render_distance, 2);
+	}
+
+	#[test]
+	fn every_tweak_round_trips() {
+		let mut tweaks = Tweaks::default();
+		for (name, ..) in TWEAKS {
+			let (shown, _) = run_command(&format!("get {}", name), &mut tweaks, 0).unwrap();
+			let value = shown.rsplit(' ').next().unwrap();
+			let (set, _) = run_command(&format!("set {} {}", name, value), &mut tweaks, 0).unwrap();
+			assert_eq!(set, shown);
+		}
+	}
+}
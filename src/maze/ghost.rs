@@ -0,0 +1,246 @@
+use super::{
+	solid_color::{SolidColorAssets, SolidColorUniforms},
+	ControlMode, Reset,
+};
+use crate::prelude::*;
+use glam::{vec2, vec3, Mat4, Quat, Vec3, Vec4};
+use serde_derive::{Deserialize, Serialize};
+
+/// Seconds between the camera poses of a recording
+const SAMPLE_INTERVAL: f32 = 0.1;
+/// Part of the ghost's pixels drawn, see the solid color shader
+const GHOST_OPACITY: f32 = 0.5;
+
+/// Camera path of a run through the maze generated from `seed`, one pose every
+/// `SAMPLE_INTERVAL` seconds
+#[derive(Serialize, Deserialize)]
+struct Recording {
+	seed: u64,
+	/// Translation and rotation quaternion of each pose
+	samples: Vec<([f32; 3], [f32; 4])>,
+}
+
+impl Recording {
+	fn new(seed: u64) -> Self {
+		Self {
+			seed,
+			samples: vec![],
+		}
+	}
+
+	fn path(seed: u64) -> String {
+		format!("maze_walk_ghost_{}.yml", seed)
+	}
+
+	/// The fastest completed run of the maze generated from `seed`, if one was recorded
+	fn load(seed: u64) -> Option<Self> {
+		let contents = std::fs::read_to_string(Self::path(seed)).ok()?;
+		match serde_yaml::from_str::<Recording>(&contents) {
+			Ok(recording) if recording.seed == seed => Some(recording),
+			Ok(_) => None,
+			Err(e) => {
+				warn!("Failed to read ghost from {}: {}", Self::path(seed), e);
+				None
+			}
+		}
+	}
+
+	fn save(&self) -> Result<(), String> {
+		let contents = serde_yaml::to_string(self).map_err(|e| e.to_string())?;
+		std::fs::write(Self::path(self.seed), contents).map_err(|e| e.to_string())
+	}
+
+	fn duration(&self) -> f32 {
+		self.samples.len().saturating_sub(1) as f32 * SAMPLE_INTERVAL
+	}
+
+	/// The pose `time` seconds into the run, between the two closest samples. None once the
+	/// recorded run has ended.
+	fn pose_at(&self, time: f32) -> Option<(Vec3, Quat)> {
+		let position = time.max(0.) / SAMPLE_INTERVAL;
+		let (from, to) = match self.samples.get(position as usize..=position as usize + 1) {
+			Some([from, to]) => (from, to),
+			_ => return None,
+		};
+		let t = position.fract();
+		let rotation = |r: [f32; 4]| Quat::from_xyzw(r[0], r[1], r[2], r[3]);
+		Some((
+			Vec3::from(from.0).lerp(Vec3::from(to.0), t),
+			rotation(from.1).slerp(rotation(to.1), t),
+		))
+	}
+}
+
+/// Translation and rotation of `transform` as stored in a recording
+fn pose_of(transform: &GlobalTransform) -> ([f32; 3], [f32; 4]) {
+	(transform.translation.into(), Vec4::from(transform.rotation).into())
+}
+
+/// Records the camera path of the current run
+pub struct GhostRecorder {
+	recording: Recording,
+	elapsed: f32,
+	/// Run restored from a save, recorded from where it was saved instead of the start
+	partial: bool,
+}
+
+/// Replays the fastest earlier run of the same maze as a ghost
+pub struct GhostPlayback {
+	recording: Option<Recording>,
+	elapsed: f32,
+}
+
+/// Marker of the entity following the replayed camera path
+pub struct Ghost;
+
+pub struct GhostAssets {
+	mesh: Handle<Mesh>,
+}
+
+pub fn load_ghost_assets(mut cmd: Commands, mut meshes: ResMut<Assets<Mesh>>) {
+	cmd.insert_resource(GhostAssets {
+		mesh: meshes.add(build_ghost_mesh()),
+	});
+}
+
+/// Starts recording a new run of the maze generated from `seed`, and replaying the recorded one.
+/// A `partial` run is never saved.
+pub fn start_ghost_run(cmd: &mut Commands, seed: u64, partial: bool) {
+	cmd.insert_resource(GhostRecorder {
+		recording: Recording::new(seed),
+		elapsed: 0.,
+		partial,
+	});
+	cmd.insert_resource(GhostPlayback {
+		recording: Recording::load(seed),
+		elapsed: 0.,
+	});
+}
+
+pub fn record_ghost(
+	mut recorder: ResMut<GhostRecorder>,
+	time: Res<Time>,
+	control_mode: Res<ControlMode>,
+	q_cam: Query<&GlobalTransform, With<Camera>>,
+) {
+	// the overhead camera floats away from the player, who stays where they were
+	let pose = if *control_mode == ControlMode::Overhead {
+		match recorder.recording.samples.last() {
+			Some(last) => *last,
+			None => return,
+		}
+	} else {
+		pose_of(q_cam.single().unwrap())
+	};
+	// a slow frame repeats the pose, to keep the samples evenly spaced in time
+	while recorder.recording.samples.len() as f32 * SAMPLE_INTERVAL <= recorder.elapsed {
+		recorder.recording.samples.push(pose);
+	}
+	recorder.elapsed += time.delta_seconds();
+}
+
+/// Keeps the recording of a completed run if it beat the one being replayed
+pub fn save_ghost_recording(recorder: Res<GhostRecorder>, playback: Res<GhostPlayback>) {
+	if recorder.partial {
+		return;
+	}
+	let recording = &recorder.recording;
+	if let Some(best) = &playback.recording {
+		if best.duration() <= recording.duration() {
+			return;
+		}
+	}
+	match recording.save() {
+		Ok(()) => info!("Saved ghost to {}", Recording::path(recording.seed)),
+		Err(e) => warn!("Failed to save ghost to {}: {}", Recording::path(recording.seed), e),
+	}
+}
+
+pub fn update_ghost(
+	mut cmd: Commands,
+	mut playback: ResMut<GhostPlayback>,
+	time: Res<Time>,
+	assets: Res<GhostAssets>,
+	solid_color: Res<SolidColorAssets>,
+	mut q: Query<(Entity, &mut SolidColorUniforms), With<Ghost>>,
+) {
+	playback.elapsed += time.delta_seconds();
+	let pose = match playback.recording {
+		Some(ref recording) => recording.pose_at(playback.elapsed),
+		None => return,
+	};
+	let ghost = q.single_mut().ok();
+	match (pose, ghost) {
+		(Some((translation, rotation)), Some((_, mut uniforms))) => {
+			uniforms.model = Mat4::from_rotation_translation(rotation, translation);
+		}
+		(Some((translation, rotation)), None) => {
+			let (mesh, shader, uniforms) = solid_color.bundle(
+				assets.mesh.clone(),
+				vec3(0.6, 0.8, 1.0),
+				Mat4::from_rotation_translation(rotation, translation),
+			);
+			cmd.spawn_bundle((
+				mesh,
+				shader,
+				SolidColorUniforms {
+					alpha: GHOST_OPACITY,
+					..uniforms
+				},
+				Ghost,
+				Reset,
+			));
+		}
+		// the replayed run reached the exit
+		(None, Some((entity, _))) => cmd.entity(entity).despawn(),
+		(None, None) => {}
+	}
+}
+
+/// A pyramid pointing along -Z like the camera, with its base behind the camera position
+fn build_ghost_mesh() -> Mesh {
+	let vertex = |x: f32, y: f32, z: f32| Vertex {
+		pos: vec3(x, y, z),
+		normal: Vec3::Z,
+		uv: vec2(0., 0.),
+		color: Vertex::WHITE,
+	};
+	Mesh {
+		vertices: vec![
+			vertex(0., 0., -0.15),
+			vertex(-0.1, -0.1, 0.1),
+			vertex(0.1, -0.1, 0.1),
+			vertex(0.1, 0.1, 0.1),
+			vertex(-0.1, 0.1, 0.1),
+		],
+		// both windings of every face, so it doesn't matter which way they face
+		indices: vec![
+			0, 1, 2, 0, 2, 1, 0, 2, 3, 0, 3, 2, 0, 3, 4, 0, 4, 3, 0, 4, 1, 0, 1, 4, 1, 2, 3, 1, 3,
+			2, 1, 3, 4, 1, 4, 3,
+		],
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn replay_interpolates_between_samples() {
+		let mut recording = Recording::new(1);
+		for (x, yaw) in vec![(0., 0.), (1., 1.), (3., 1.)] {
+			let mut transform = GlobalTransform::from_xyz(x, 0., 0.);
+			transform.rotation = Quat::from_rotation_y(yaw);
+			recording.samples.push(pose_of(&transform));
+		}
+		assert!((recording.duration() - SAMPLE_INTERVAL * 2.).abs() < 1e-6);
+
+		let (translation, rotation) = recording.pose_at(SAMPLE_INTERVAL * 0.5).unwrap();
+		assert!(translation.abs_diff_eq(vec3(0.5, 0., 0.), 1e-5));
+		assert!(rotation.abs_diff_eq(Quat::from_rotation_y(0.5), 1e-5));
+		let (translation, _) = recording.pose_at(SAMPLE_INTERVAL * 1.25).unwrap();
+		assert!(translation.abs_diff_eq(vec3(1.5, 0., 0.), 1e-5));
+		assert!(recording.pose_at(SAMPLE_INTERVAL * 2.5).is_none());
+		assert!(Recording::new(1).pose_at(0.).is_none());
+	}
+}
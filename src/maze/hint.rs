@@ -0,0 +1,113 @@
+use super::{
+	node_at_world_pos, node_to_world,
+	solid_color::{SolidColorAssets, SolidColorUniforms},
+	Chunk, CurrentChunk, Reset,
+};
+use crate::key_bindings::{Action, KeyBindings};
+use crate::prelude::*;
+use glam::{vec2, vec3, Mat4, Quat, Vec3};
+
+const ARROW_DISTANCE: f32 = 0.6;
+const ARROW_HEIGHT: f32 = -0.2;
+
+pub struct HintAssets {
+	arrow_mesh: Handle<Mesh>,
+}
+
+/// Arrow pointing along the shortest path towards the exit of the current chunk
+pub struct ExitHintArrow;
+
+pub fn load_hint_assets(mut cmd: Commands, mut meshes: ResMut<Assets<Mesh>>) {
+	cmd.insert_resource(HintAssets {
+		arrow_mesh: meshes.add(build_arrow_mesh()),
+	});
+}
+
+pub fn toggle_exit_hint(
+	mut cmd: Commands,
+	input: Res<Keyboard>,
+	bindings: Res<KeyBindings>,
+	hint_assets: Res<HintAssets>,
+	solid_color: Res<SolidColorAssets>,
+	q: Query<Entity, With<ExitHintArrow>>,
+) {
+	if bindings.was_just_pressed(Action::ToggleExitHint, &input) {
+		if let Ok(arrow_entity) = q.single() {
+			cmd.entity(arrow_entity).despawn();
+		} else {
+			cmd.spawn_bundle(solid_color.bundle(
+				hint_assets.arrow_mesh.clone(),
+				vec3(0.3, 1.0, 0.5),
+				Mat4::IDENTITY,
+			))
+			.insert_bundle((ExitHintArrow, Reset));
+		}
+	}
+}
+
+pub fn update_exit_hint(
+	mut q_arrow: Query<&mut SolidColorUniforms, With<ExitHintArrow>>,
+	q_cam: Query<&GlobalTransform, With<Camera>>,
+	q_chunks: Query<&Chunk>,
+	current_chunk: Res<CurrentChunk>,
+) {
+	let mut uniforms = match q_arrow.single_mut() {
+		Ok(uniforms) => uniforms,
+		Err(_) => return,
+	};
+	let chunk = match current_chunk.0.and_then(|e| q_chunks.get(e).ok()) {
+		Some(chunk) => chunk,
+		None => return,
+	};
+	let cam_transform = q_cam.single().unwrap();
+	let player_node = match node_at_world_pos(chunk, cam_transform.translation) {
+		Some(idx) => chunk.maze[idx],
+		None => return,
+	};
+	let target = if player_node.idx() == chunk.exit.node {
+		// next chunk entrance, or straight through the exit if it's not generated yet
+		q_chunks
+			.iter()
+			.find(|c| c.index == chunk.index + 1)
+			.map(|c| node_to_world(&c.maze[c.entrance.node], c))
+			.unwrap_or_else(|| {
+				let (x, z) = chunk.exit.side.get_offset();
+				node_to_world(&player_node, chunk) + vec3(x as f32, 0., z as f32)
+			})
+	} else {
+		let path = chunk.maze.path_to(&player_node, &chunk.maze[chunk.exit.node]);
+		match path.get(1) {
+			Some(next_node) => node_to_world(next_node, chunk),
+			None => return,
+		}
+	};
+
+	let forward = cam_transform.rotation * -Vec3::Z;
+	let forward = vec2(forward.x, forward.z).normalize_or_zero();
+	let arrow_pos = cam_transform.translation
+		+ vec3(forward.x, 0., forward.y) * ARROW_DISTANCE
+		+ vec3(0., ARROW_HEIGHT, 0.);
+	let direction = target - arrow_pos;
+	// the arrow mesh points along -Z
+	let yaw = (-direction.x).atan2(-direction.z);
+	uniforms.model = Mat4::from_rotation_translation(Quat::from_rotation_y(yaw), arrow_pos);
+}
+
+fn build_arrow_mesh() -> Mesh {
+	let vertex = |x: f32, z: f32| Vertex {
+		pos: vec3(x, 0., z),
+		normal: Vec3::Y,
+		uv: vec2(0., 0.),
+		color: Vertex::WHITE,
+	};
+	Mesh {
+		vertices: vec![
+			vertex(0., -0.15),
+			vertex(-0.1, 0.1),
+			vertex(0., 0.03),
+			vertex(0.1, 0.1),
+		],
+		// both windings, so the arrow is visible from above and below
+		indices: vec![0, 1, 2, 0, 2, 3, 0, 2, 1, 0, 3, 2],
+	}
+}
@@ -0,0 +1,56 @@
+use super::Uniforms;
+use crate::prelude::*;
+use miniquad::KeyCode;
+
+/// Lighting switches for checking the normal maps, kept across mazes
+pub struct LightingDebug {
+	pub normal_maps_enabled: bool,
+	/// Output the normal map perturbed surface normal as color instead of the lit surface
+	pub show_normals: bool,
+}
+
+impl Default for LightingDebug {
+	fn default() -> Self {
+		Self {
+			normal_maps_enabled: true,
+			show_normals: false,
+		}
+	}
+}
+
+/// Normal map intensity of the surface material, restored when normal mapping is re-enabled
+pub struct NormalMapIntensity(pub f32);
+
+pub fn read_lighting_debug_input(mut debug: ResMut<LightingDebug>, input: Res<Keyboard>) {
+	// Ctrl+N toggles the no-clip cheat instead
+	let ctrl = input.is_pressed(KeyCode::LeftControl) || input.is_pressed(KeyCode::RightControl);
+	if input.was_just_pressed(KeyCode::N) && !ctrl {
+		debug.normal_maps_enabled = !debug.normal_maps_enabled;
+	}
+	if input.was_just_pressed(KeyCode::F3) {
+		debug.show_normals = !debug.show_normals;
+	}
+}
+
+/// Drawing back faces goes with the lighting switches, for checking newly imported meshes
+pub fn toggle_backfaces(mut settings: ResMut<RenderSettings>, input: Res<Keyboard>) {
+	if input.was_just_pressed(KeyCode::F6) {
+		settings.show_backfaces = !settings.show_backfaces;
+		info!("Back faces {}", if settings.show_backfaces { "shown" } else { "culled" });
+	}
+}
+
+pub fn apply_lighting_debug(
+	debug: Res<LightingDebug>,
+	mut q: Query<(&mut Uniforms, &NormalMapIntensity)>,
+) {
+	let show_normals = if debug.show_normals { 1. } else { 0. };
+	for (mut uniforms, intensity) in q.iter_mut() {
+		uniforms.normal_map_intensity = if debug.normal_maps_enabled {
+			intensity.0
+		} else {
+			0.
+		};
+		uniforms.show_normals = show_normals;
+	}
+}
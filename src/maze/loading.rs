@@ -0,0 +1,92 @@
+use crate::prelude::*;
+use glam::vec2;
+
+const BAR_SIZE: (f32, f32) = (240., 6.);
+const SPINNER_SIZE: f32 = 8.;
+const SPINNER_RADIUS: f32 = 16.;
+/// Distance of the spinner center above the progress bar
+const SPINNER_OFFSET: f32 = 40.;
+
+/// Part of the screen shown while the maze assets load
+pub enum LoadingScreenElement {
+	BarBackground,
+	BarFill,
+	Spinner,
+}
+
+/// Finished and requested file loads of all the asset types waited for before playing
+pub fn load_progress(
+	textures: &Assets<Texture>,
+	shaders: &Assets<Shader>,
+	sounds: &Assets<Sound>,
+) -> f32 {
+	let parts = [
+		textures.load_progress(),
+		shaders.load_progress(),
+		sounds.load_progress(),
+	];
+	let (loaded, requested) = parts
+		.iter()
+		.fold((0, 0), |(l, r), (loaded, requested)| (l + loaded, r + requested));
+	if requested == 0 {
+		1.
+	} else {
+		loaded as f32 / requested as f32
+	}
+}
+
+pub fn spawn_loading_screen(mut cmd: Commands) {
+	for (element, color) in vec![
+		(LoadingScreenElement::BarBackground, Color::rgb(0.15, 0.15, 0.15)),
+		(LoadingScreenElement::BarFill, Color::rgb(0.8, 0.8, 0.8)),
+		(LoadingScreenElement::Spinner, Color::rgb(0.8, 0.8, 0.8)),
+	] {
+		let zero = Rect::from_center(Vec2::ZERO, Vec2::ZERO);
+		cmd.spawn_bundle((
+			element,
+			ScreenQuad {
+				color,
+				..ScreenQuad::new(zero)
+			},
+		));
+	}
+}
+
+pub fn update_loading_screen(
+	mut q: Query<(&LoadingScreenElement, &mut ScreenQuad)>,
+	textures: Res<Assets<Texture>>,
+	shaders: Res<Assets<Shader>>,
+	sounds: Res<Assets<Sound>>,
+	window: Res<WindowSize>,
+	time: Res<Time>,
+) {
+	let progress = load_progress(&textures, &shaders, &sounds);
+	let center = vec2(window.width, window.height) / 2.;
+	let bar_size = vec2(BAR_SIZE.0, BAR_SIZE.1);
+	let bar = Rect::from_center(center, bar_size);
+	for (element, mut quad) in q.iter_mut() {
+		let rect = match element {
+			LoadingScreenElement::BarBackground => bar,
+			LoadingScreenElement::BarFill => Rect {
+				right: bar.left + bar_size.x * progress,
+				..bar
+			},
+			LoadingScreenElement::Spinner => {
+				let angle = time.seconds_since_startup() as f32 * std::f32::consts::TAU;
+				let orbit = vec2(angle.cos(), angle.sin()) * SPINNER_RADIUS;
+				let spinner_center = center - vec2(0., SPINNER_OFFSET) + orbit;
+				Rect::from_center(spinner_center, Vec2::splat(SPINNER_SIZE))
+			}
+		};
+		// changed quads get their meshes rebuilt
+		if quad.rect != rect {
+			quad.rect = rect;
+		}
+	}
+}
+
+pub fn despawn_loading_screen(mut cmd: Commands, q: Query<Entity, With<LoadingScreenElement>>) {
+	for entity in q.iter() {
+		cmd.entity(entity).despawn();
+	}
+}
@@ -0,0 +1,56 @@
+use super::{Chunk, ControlMode, CurrentChunk, NoClip, CHUNK_SIZE, PI};
+use crate::prelude::*;
+use glam::{vec3, Quat};
+use miniquad::KeyCode;
+
+/// Camera state to restore when leaving the overhead view
+struct SavedView {
+	transform: GlobalTransform,
+	control_mode: ControlMode,
+}
+
+#[derive(Default)]
+pub struct OverheadView(Option<SavedView>);
+
+impl OverheadView {
+	pub fn is_active(&self) -> bool {
+		self.0.is_some()
+	}
+}
+
+pub fn toggle_overhead_view(
+	mut cmd: Commands,
+	mut overhead: ResMut<OverheadView>,
+	mut control_mode: ResMut<ControlMode>,
+	input: Res<Keyboard>,
+	current_chunk: Res<CurrentChunk>,
+	q_chunks: Query<&Chunk>,
+	mut q_cam: Query<(Entity, &mut GlobalTransform, &mut Camera)>,
+) {
+	if !input.was_just_pressed(KeyCode::F2) {
+		return;
+	}
+	let (cam_entity, mut cam_transform, mut camera) = q_cam.single_mut().unwrap();
+	if let Some(saved) = overhead.0.take() {
+		*cam_transform = saved.transform;
+		*control_mode = saved.control_mode;
+		camera.projection = Projection::Perspective;
+		if saved.control_mode != ControlMode::Hover {
+			cmd.entity(cam_entity).remove::<NoClip>();
+		}
+	} else if let Some(chunk) = current_chunk.0.and_then(|e| q_chunks.get(e).ok()) {
+		overhead.0 = Some(SavedView {
+			transform: cam_transform.clone(),
+			control_mode: *control_mode,
+		});
+		*control_mode = ControlMode::Overhead;
+		let center = chunk.coords.to_rect().center();
+		// high enough for the whole chunk to fit the field of view, like a perspective view would
+		let extent = CHUNK_SIZE as f32;
+		let height = (extent / 2.) / (camera.field_of_view.to_radians() / 2.).tan() + 1.;
+		cam_transform.translation = vec3(center.x, height, center.y) - vec3(0.5, 0., 0.5);
+		cam_transform.rotation = Quat::from_rotation_x(-PI / 2.);
+		camera.projection = Projection::Orthographic { height: extent };
+		cmd.entity(cam_entity).insert(NoClip);
+	}
+}
@@ -0,0 +1,74 @@
+use super::{Chunk, ChunkEntered, Reset};
+use crate::prelude::*;
+use glam::vec2;
+
+const TIMER_SCALE: f32 = 3.;
+const TIMER_MARGIN: f32 = 8.;
+
+/// Progress of the current run, reset when a new maze is generated
+#[derive(Default, Clone, Debug)]
+pub struct RunStats {
+	pub elapsed_seconds: f32,
+	/// Index of the farthest chunk entered
+	pub chunks_traversed: usize,
+}
+
+/// Sent with the final stats when the exit of a finite maze is reached
+pub struct RunCompleted(pub RunStats);
+
+struct RunTimerDisplay;
+
+pub fn spawn_run_timer(cmd: &mut Commands) {
+	cmd.spawn_bundle((
+		RunTimerDisplay,
+		Text {
+			value: format_run_time(0.),
+			position: vec2(TIMER_MARGIN, TIMER_MARGIN),
+			scale: TIMER_SCALE,
+			color: Color::rgb(1., 1., 1.),
+		},
+		Reset,
+	));
+}
+
+pub fn update_run_stats(
+	mut stats: ResMut<RunStats>,
+	time: Res<Time>,
+	mut entered_event: EventReader<ChunkEntered>,
+	q_chunks: Query<&Chunk>,
+) {
+	stats.elapsed_seconds += time.delta_seconds();
+	for ChunkEntered(entered) in entered_event.iter() {
+		if let Ok(chunk) = q_chunks.get(*entered) {
+			stats.chunks_traversed = stats.chunks_traversed.max(chunk.index);
+		}
+	}
+}
+
+pub fn update_run_timer_display(
+	stats: Res<RunStats>,
+	window: Res<WindowSize>,
+	mut q: Query<&mut Text, With<RunTimerDisplay>>,
+) {
+	let value = format_run_time(stats.elapsed_seconds);
+	// glyphs advance by 4 pixels at scale 1
+	let width = value.len() as f32 * 4. * TIMER_SCALE;
+	let position = vec2(window.width - width - TIMER_MARGIN, TIMER_MARGIN);
+	for mut text in q.iter_mut() {
+		// avoid rebuilding the text mesh when nothing changed
+		if text.value != value || text.position != position {
+			text.value = value.clone();
+			text.position = position;
+		}
+	}
+}
+
+pub fn format_run_time(seconds: f32) -> String {
+	let tenths = (seconds * 10.) as u32;
+	format!(
+		"TIME: {}:{:02}.{}",
+		tenths / 600,
+		tenths / 10 % 60,
+		tenths % 10
+	)
+}
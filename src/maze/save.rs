@@ -0,0 +1,253 @@
+use super::{
+	chunk_rng, chunks_behind, generate_chunk, generate_chunk_layout, next_chunk_placement,
+	tweaks::Tweaks, visited::VisitedCells, AutoWalkState, Chunk, ControlMode, ControlModeChanged,
+	CurrentChunk, MazeAssets, Random, RequestedSeed, RotationEuler, SidedNode, MAZE_SIZE,
+};
+use crate::maze_gen::GridMaze;
+use crate::prelude::*;
+use glam::Vec3;
+use miniquad::KeyCode;
+use serde_derive::{Deserialize, Serialize};
+
+const SAVE_PATH: &str = "maze_walk_save.yml";
+
+/// A run in progress, written with F5 and resumed with F9. Chunks are not stored, they're
+/// generated again from the seed.
+#[derive(Serialize, Deserialize)]
+pub struct SaveState {
+	seed: u64,
+	/// Index and exit of each chunk generated so far, see `Random::history`
+	chunks: Vec<(usize, SidedNode)>,
+	current_chunk: usize,
+	camera_position: [f32; 3],
+	yaw: f32,
+	pitch: f32,
+	control_mode: ControlMode,
+	/// Visited cells by chunk index and node index
+	visited: Vec<(usize, usize)>,
+}
+
+impl SaveState {
+	/// Checks that the chunk chain can be replayed, so a damaged save can't panic the generator
+	fn validate(&self) -> Result<(), String> {
+		let grid = GridMaze::new(MAZE_SIZE, MAZE_SIZE);
+		let mut chain_len = 0;
+		for (index, exit) in self.chunks.iter() {
+			// the first chunk comes first, each later one continues an existing chunk
+			if *index > chain_len || (*index == 0) != (chain_len == 0) {
+				return Err(format!("chunk {} is out of order", index));
+			}
+			if !grid.get_edge_nodes(exit.side).iter().any(|n| n.idx() == exit.node) {
+				return Err(format!("chunk {} exit is not on its {:?} edge", index, exit.side));
+			}
+			// regenerating a chunk replaces it and the ones after it
+			chain_len = index + 1;
+		}
+		if self.current_chunk >= chain_len {
+			return Err(format!("current chunk {} was never generated", self.current_chunk));
+		}
+		Ok(())
+	}
+}
+
+/// Save to restore the next time the maze is generated
+#[derive(Default)]
+pub struct PendingLoad(Option<SaveState>);
+
+impl PendingLoad {
+	/// Whether the maze about to be generated continues a saved run
+	pub fn is_pending(&self) -> bool {
+		self.0.is_some()
+	}
+}
+
+pub fn save_run(
+	input: Res<Keyboard>,
+	random: Res<Random>,
+	current_chunk: Res<CurrentChunk>,
+	control_mode: Res<ControlMode>,
+	auto_walk: Res<AutoWalkState>,
+	visited: Res<VisitedCells>,
+	q_chunks: Query<&Chunk>,
+	q_cam: Query<(&GlobalTransform, &RotationEuler), With<Camera>>,
+) {
+	if !input.was_just_pressed(KeyCode::F5) {
+		return;
+	}
+	// the overhead camera floats above the chunk, away from the player
+	if *control_mode == ControlMode::Overhead {
+		warn!("Leave the overhead view to save");
+		return;
+	}
+	let current_chunk = match current_chunk.0.and_then(|e| q_chunks.get(e).ok()) {
+		Some(chunk) => chunk.index,
+		None => return,
+	};
+	let (transform, euler) = q_cam.single().unwrap();
+	// resume auto walk from a maze node, rather than partway through a step
+	let (camera_position, yaw) = match auto_walk.tween_progress {
+		Some(_) if *control_mode == ControlMode::AutoWalk => {
+			(auto_walk.translation_to, auto_walk.rotation_to)
+		}
+		_ => (transform.translation, euler.yaw),
+	};
+	let mut visited = visited.0.iter().copied().collect::<Vec<_>>();
+	visited.sort_unstable();
+	let save = SaveState {
+		seed: random.seed,
+		chunks: random.history.clone(),
+		current_chunk,
+		camera_position: camera_position.into(),
+		yaw,
+		pitch: euler.pitch,
+		control_mode: *control_mode,
+		visited,
+	};
+	let written = serde_yaml::to_string(&save)
+		.map_err(|e| e.to_string())
+		.and_then(|contents| std::fs::write(SAVE_PATH, contents).map_err(|e| e.to_string()));
+	match written {
+		Ok(()) => info!("Saved run to {}", SAVE_PATH),
+		Err(e) => warn!("Failed to save run to {}: {}", SAVE_PATH, e),
+	}
+}
+
+pub fn load_run(
+	input: Res<Keyboard>,
+	mut pending_load: ResMut<PendingLoad>,
+	mut requested_seed: ResMut<RequestedSeed>,
+	mut state: ResMut<State>,
+) {
+	if !input.was_just_pressed(KeyCode::F9) {
+		return;
+	}
+	let save = std::fs::read_to_string(SAVE_PATH)
+		.map_err(|e| e.to_string())
+		.and_then(|contents| {
+			serde_yaml::from_str::<SaveState>(&contents).map_err(|e| e.to_string())
+		})
+		.and_then(|save| save.validate().map(|_| save));
+	match save {
+		Ok(save) => {
+			requested_seed.0 = Some(save.seed);
+			pending_load.0 = Some(save);
+			state.schedule_transition(AppState::Play);
+		}
+		Err(e) => warn!("Failed to load run from {}: {}", SAVE_PATH, e),
+	}
+}
+
+/// Runs after `init_play_state` generated the first chunk from the saved seed. Replays the
+/// remaining chunk generations in order to rebuild the chain of chunks the saved run went
+/// through, and spawns the chunks that were kept.
+pub fn restore_saved_run(
+	mut cmd: Commands,
+	mut pending_load: ResMut<PendingLoad>,
+	mut random: ResMut<Random>,
+	mut assets: ResMut<MazeAssets>,
+	mut meshes: ResMut<Assets<Mesh>>,
+	tweaks: Res<Tweaks>,
+	mut visited: ResMut<VisitedCells>,
+	mut control_mode: ResMut<ControlMode>,
+	mut mode_changed: EventWriter<ControlModeChanged>,
+	q_chunks: Query<(Entity, &Chunk)>,
+	mut q_cam: Query<(&mut GlobalTransform, &mut RotationEuler), With<Camera>>,
+) {
+	let save = match pending_load.0.take() {
+		Some(save) => save,
+		None => return,
+	};
+	// chunks further behind the player were despawned
+	let chunks_behind = chunks_behind(&tweaks);
+	let is_kept = |index: usize| index + chunks_behind >= save.current_chunk;
+	let (first_entity, first_chunk) = q_chunks.single().expect("get first chunk");
+	if !is_kept(0) {
+		cmd.entity(first_entity).despawn_recursive();
+	}
+
+	let mut chain = vec![first_chunk.clone()];
+	for (i, (index, exit)) in save.chunks.iter().enumerate().skip(1) {
+		let index = *index;
+		let (coords, entrance) = next_chunk_placement(&chain[index - 1]);
+		let replaced_later = save.chunks[i + 1..].iter().any(|(later, _)| *later == index);
+		let chunk = if is_kept(index) && !replaced_later {
+			generate_chunk(
+				&mut cmd,
+				&mut assets,
+				&mut meshes,
+				&tweaks,
+				index,
+				coords,
+				Some(entrance),
+				Some(exit.clone()),
+				&[],
+				random.seed,
+			)
+		} else {
+			let layout = generate_chunk_layout(
+				&tweaks,
+				coords,
+				Some(entrance),
+				Some(exit.clone()),
+				&[],
+				&mut chunk_rng(random.seed, index),
+			);
+			Chunk {
+				index,
+				coords,
+				maze: layout.maze,
+				entrance: layout.entrance,
+				exit: layout.exit,
+			}
+		};
+		chain.truncate(index);
+		chain.push(chunk);
+	}
+	random.history = save.chunks;
+
+	let (mut transform, mut euler) = q_cam.single_mut().unwrap();
+	transform.translation = Vec3::from(save.camera_position);
+	euler.yaw = save.yaw;
+	euler.pitch = save.pitch;
+	*control_mode = save.control_mode;
+	mode_changed.send(ControlModeChanged(save.control_mode));
+	visited.0 = save.visited.into_iter().collect();
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::maze_gen::GridDirection;
+
+	#[test]
+	fn reject_unreplayable_saves() {
+		let grid = GridMaze::new(MAZE_SIZE, MAZE_SIZE);
+		let exit = |side: GridDirection| SidedNode {
+			node: grid.get_edge_nodes(side)[0].idx(),
+			side,
+		};
+		let save = |chunks: Vec<(usize, SidedNode)>, current_chunk: usize| SaveState {
+			seed: 1,
+			chunks,
+			current_chunk,
+			camera_position: [0.; 3],
+			yaw: 0.,
+			pitch: 0.,
+			control_mode: ControlMode::Manual,
+			visited: vec![],
+		};
+		use GridDirection::*;
+		let valid = vec![(0, exit(Up)), (1, exit(Left)), (2, exit(Up)), (2, exit(Right))];
+		assert!(save(valid.clone(), 2).validate().is_ok());
+		assert!(save(valid, 3).validate().is_err());
+		// skips chunk 1
+		assert!(save(vec![(0, exit(Up)), (2, exit(Up))], 0).validate().is_err());
+		assert!(save(vec![(1, exit(Up))], 0).validate().is_err());
+		assert!(save(vec![], 0).validate().is_err());
+		let inner_node = SidedNode {
+			node: GridMaze::idx_1d(1, 1, MAZE_SIZE),
+			side: Up,
+		};
+		assert!(save(vec![(0, inner_node)], 0).validate().is_err());
+	}
+}
@@ -0,0 +1,106 @@
+use super::Random;
+use crate::prelude::*;
+use glam::vec2;
+use miniquad::KeyCode;
+
+/// Seed to use the next time the maze is generated. A random seed is used if not set.
+#[derive(Default)]
+pub struct RequestedSeed(pub Option<u64>);
+
+/// Digits typed so far, while seed entry is active
+#[derive(Default)]
+pub struct SeedEntry(Option<String>);
+
+pub struct SeedDisplay;
+
+pub fn spawn_seed_display(cmd: &mut Commands, seed: u64) {
+	cmd.spawn_bundle((
+		SeedDisplay,
+		Text {
+			value: format_seed(seed),
+			position: vec2(8., 60.),
+			scale: 2.,
+			color: Color::rgb(1., 1., 1.),
+		},
+		super::Reset,
+	));
+}
+
+pub fn read_seed_entry_input(
+	mut entry: ResMut<SeedEntry>,
+	mut requested_seed: ResMut<RequestedSeed>,
+	mut state: ResMut<State>,
+	input: Res<Keyboard>,
+) {
+	let confirmed =
+		input.was_just_pressed(KeyCode::Enter) || input.was_just_pressed(KeyCode::KpEnter);
+	if entry.0.is_none() {
+		if confirmed {
+			entry.0 = Some(String::new());
+		}
+		return;
+	}
+	if confirmed {
+		let digits = entry.0.take().unwrap();
+		// an empty entry rolls a new random seed
+		requested_seed.0 = if digits.is_empty() {
+			None
+		} else {
+			match digits.parse::<u64>() {
+				Ok(seed) => Some(seed),
+				Err(e) => {
+					warn!("Invalid seed {}: {}", digits, e);
+					return;
+				}
+			}
+		};
+		state.schedule_transition(AppState::Play);
+		return;
+	}
+	let pressed = input.get_just_pressed().copied().collect::<Vec<_>>();
+	let digits = entry.0.as_mut().unwrap();
+	for key in pressed.into_iter() {
+		if key == KeyCode::Backspace {
+			digits.pop();
+		} else if let Some(digit) = key_to_digit(key) {
+			digits.push(digit);
+		}
+	}
+}
+
+pub fn update_seed_display(
+	entry: Res<SeedEntry>,
+	random: Res<Random>,
+	mut q: Query<&mut Text, With<SeedDisplay>>,
+) {
+	if entry.is_changed() {
+		for mut text in q.iter_mut() {
+			text.value = match entry.0 {
+				Some(ref digits) => format!("NEW SEED: {}_", digits),
+				None => format_seed(random.seed),
+			};
+		}
+	}
+}
+
+fn format_seed(seed: u64) -> String {
+	format!("SEED: {}", seed)
+}
+
+fn key_to_digit(key: KeyCode) -> Option<char> {
+	use KeyCode::*;
+	let digit = match key {
+		Key0 | Kp0 => 0,
+		Key1 | Kp1 => 1,
+		Key2 | Kp2 => 2,
+		Key3 | Kp3 => 3,
+		Key4 | Kp4 => 4,
+		Key5 | Kp5 => 5,
+		Key6 | Kp6 => 6,
+		Key7 | Kp7 => 7,
+		Key8 | Kp8 => 8,
+		Key9 | Kp9 => 9,
+		_ => return None,
+	};
+	std::char::from_digit(digit, 10)
+}
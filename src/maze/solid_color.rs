@@ -0,0 +1,78 @@
+use crate::prelude::*;
+use glam::{Mat4, Vec3};
+use miniquad::UniformType;
+
+/// Unlit, texture-free shader for markers and debug geometry
+pub struct SolidColorAssets {
+	pub shader: Handle<Shader>,
+}
+
+impl SolidColorAssets {
+	/// Components drawing `mesh` in a single color at `model`, without binding any textures
+	pub fn bundle(
+		&self,
+		mesh: Handle<Mesh>,
+		color: Vec3,
+		model: Mat4,
+	) -> (Handle<Mesh>, Handle<Shader>, SolidColorUniforms) {
+		(
+			mesh,
+			self.shader.clone(),
+			SolidColorUniforms {
+				model,
+				color,
+				..Default::default()
+			},
+		)
+	}
+}
+
+#[repr(C)]
+#[derive(Clone)]
+pub struct SolidColorUniforms {
+	pub model: Mat4,
+	pub view: Mat4,
+	pub projection: Mat4,
+	pub color: Vec3,
+	/// Part of the pixels drawn, in a fine pattern that reads as translucent without blending
+	pub alpha: f32,
+}
+
+impl CameraUniforms for SolidColorUniforms {
+	fn set_camera(&mut self, camera: &CameraView) {
+		self.view = camera.view;
+		self.projection = camera.projection;
+	}
+}
+
+impl Default for SolidColorUniforms {
+	fn default() -> Self {
+		Self {
+			model: Mat4::IDENTITY,
+			view: Mat4::IDENTITY,
+			projection: Mat4::IDENTITY,
+			color: Vec3::ONE,
+			alpha: 1.,
+		}
+	}
+}
+
+pub fn load_solid_color_assets(
+	mut cmd: Commands,
+	mut shaders: ResMut<Assets<Shader>>,
+	mut shader_meta: ResMut<ShaderMetaStore>,
+) {
+	let shader = shaders.load("assets/solid_color.glsl");
+	shader_meta.set(
+		&shader,
+		&[],
+		&[
+			("model", UniformType::Mat4),
+			("view", UniformType::Mat4),
+			("projection", UniformType::Mat4),
+			("object_color", UniformType::Float3),
+			("alpha", UniformType::Float1),
+		],
+	);
+	cmd.insert_resource(SolidColorAssets { shader });
+}
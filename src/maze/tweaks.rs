@@ -1,37 +1,167 @@
-use super::Material;
+use super::{AutoWalkHand, AutoWalkMode, Material};
+use crate::maze_gen::GridDirection;
+use crate::rendering::FovAxis;
 
 pub struct Tweaks {
 	pub ambient_light_intensity: f32,
+	/// Tint of the ambient light, white keeps surfaces their own color
+	pub ambient_light_color: u32,
 	pub ceiling_material: Material,
 	pub wall_material: Material,
+	/// Number of hues evenly spaced around the color wheel that chunks cycle through, at least 1
+	pub wall_color_count: usize,
+	/// Saturation of the chunk wall colors, 0 makes every chunk gray
+	pub wall_color_saturation: f32,
+	/// Lightness of the chunk wall colors, pastel towards 1
+	pub wall_color_lightness: f32,
+	/// Tints the walls of each chunk with the next palette color, otherwise walls keep the wall
+	/// material color throughout
+	pub per_chunk_colors: bool,
 	pub floor_material: Material,
 	pub mouse_sensitivity: f32,
+	/// Half the side length of the player's square collision shape, kept below half a cell
+	pub player_radius: f32,
+	/// Height of the camera above the floor while walking, half a cell by default
+	pub eye_height: f32,
+	/// Moving the mouse up looks down
+	pub invert_look_y: bool,
+	/// Moving the mouse right looks left
+	pub invert_look_x: bool,
 	pub mouse_delta_cap: f32,
+	/// Part of the mouse look movement carried over to following frames, 0 applies it at once
+	pub mouse_smoothing: f32,
+	/// Frame rate cap for when vsync is off, 0 for unlimited. Has no effect on the web.
+	pub max_fps: u32,
+	/// Camera field of view in degrees along `field_of_view_axis`, adjusted at runtime with +/-
+	pub field_of_view: f32,
+	/// Window axis `field_of_view` applies to, Horizontal keeps ultrawide windows from stretching
+	pub field_of_view_axis: FovAxis,
+	/// Distance of the camera's near clipping plane, above 0 and below `far_clip`
+	pub near_clip: f32,
+	/// Distance past which geometry is cut off. Keep it beyond the chunks within
+	/// `render_distance`, 17 cells each, or lower it with them to draw less.
+	pub far_clip: f32,
+	/// Distance from the camera past which chunks behind the one the player is in can be
+	/// removed, so a path looping back doesn't drop chunks still in view
+	pub chunk_keep_distance: f32,
+	/// Seconds a chunk takes to dim out once it's removed behind the player
+	pub chunk_fade_duration: f32,
+	/// Number of chunks kept generated ahead of the one the player is in, at most
+	/// `render_distance`
+	pub chunk_lookahead: usize,
+	/// Number of chunks kept ahead of the one the player is in, and behind it counting the
+	/// current one. Cycled at runtime with V, at least 1.
+	pub render_distance: usize,
+	/// Number of older chunks kept behind the one the player is in, for looking back across a
+	/// chunk boundary. Follows `render_distance` if not set.
+	pub chunks_behind: Option<usize>,
+	/// Height of the walls and ceiling above the floor, one cell tall by default
+	pub ceiling_height: f32,
+	/// Height above the floor over which wall normals bend towards the floor's, softening the
+	/// lighting seam where they meet. 0 turns it off.
+	pub floor_seam_blend_height: f32,
+	/// Direction the first chunk is entered in, so runs start the same way. Random if not set.
+	pub start_facing: Option<GridDirection>,
+	/// Number of chunks in a finite maze. The maze continues indefinitely if not set.
+	pub maze_length: Option<usize>,
+	/// Minimum straight line distance from entrance to exit, as a fraction of the chunk diagonal
+	pub exit_min_distance_ratio: f32,
+	/// Number of 3x3 cell regions per chunk that are more costly to path through
+	pub difficult_region_count: usize,
+	/// Path cost of a cell in a difficult region, regular cells cost 1
+	pub difficult_region_weight: isize,
+	pub wall_diffuse_texture: String,
+	pub wall_normal_texture: String,
+	pub floor_diffuse_texture: String,
+	pub floor_normal_texture: String,
+	pub ceiling_diffuse_texture: String,
+	pub ceiling_normal_texture: String,
+	/// Anisotropic filtering level for the maze textures, capped to what the driver supports
+	pub anisotropy: f32,
+	/// Distance walked between footstep sounds, 0 turns them off
+	pub footstep_interval: f32,
+	/// Auto-walk speed in cells per second, also kept for the longer steps into the next chunk
+	pub auto_walk_speed: f32,
+	/// Seconds auto-walk takes to turn towards a new heading, regardless of the step length
+	pub auto_walk_turn_duration: f32,
+	/// Yaw added when auto-walk turns around, so it always turns the same way instead of
+	/// depending on rounding
+	pub auto_walk_reverse_bias: f32,
+	/// Whether auto-walk follows a wall or heads straight for the exit
+	pub auto_walk_mode: AutoWalkMode,
+	/// Side of the wall auto-walk follows, which changes the route taken through the same maze
+	pub auto_walk_hand: AutoWalkHand,
 }
 impl Default for Tweaks {
 	fn default() -> Self {
 		Self {
 			ambient_light_intensity: 0.1,
+			ambient_light_color: 0xFFFFFF,
 			ceiling_material: Material {
 				color: 0xFFFFFF,
 				normal_intensity: 0.6,
 				specular_strength: 0.1,
 				shininess: 8.0,
+				emissive: 0x000000,
+				emissive_strength: 0.,
 			},
 			wall_material: Material {
 				color: 0xFFFFFF,
 				normal_intensity: 0.2,
 				specular_strength: 0.2,
 				shininess: 64.0,
+				emissive: 0x000000,
+				emissive_strength: 0.,
 			},
+			wall_color_count: 8,
+			wall_color_saturation: 0.4,
+			wall_color_lightness: 0.8,
+			per_chunk_colors: true,
 			floor_material: Material {
 				color: 0xFFFFFF,
 				normal_intensity: 1.0,
 				specular_strength: 0.2,
 				shininess: 32.0,
+				emissive: 0x000000,
+				emissive_strength: 0.,
 			},
 			mouse_sensitivity: 0.0045,
+			player_radius: 0.2,
+			eye_height: 0.5,
+			invert_look_y: false,
+			invert_look_x: false,
 			mouse_delta_cap: 60.,
+			mouse_smoothing: 0.,
+			max_fps: 0,
+			field_of_view: 75.,
+			field_of_view_axis: FovAxis::Vertical,
+			near_clip: 0.1,
+			far_clip: 100.,
+			chunk_keep_distance: 30.,
+			chunk_fade_duration: 0.5,
+			chunk_lookahead: 2,
+			render_distance: 2,
+			chunks_behind: None,
+			ceiling_height: 1.,
+			floor_seam_blend_height: 0.08,
+			start_facing: None,
+			maze_length: None,
+			exit_min_distance_ratio: 0.5,
+			difficult_region_count: 2,
+			difficult_region_weight: 3,
+			wall_diffuse_texture: "assets/wall_diffuse.png".into(),
+			wall_normal_texture: "assets/wall_normal.png".into(),
+			floor_diffuse_texture: "assets/tiles_diffuse.png".into(),
+			floor_normal_texture: "assets/tiles_normal.png".into(),
+			ceiling_diffuse_texture: "assets/concrete_diffuse.png".into(),
+			ceiling_normal_texture: "assets/concrete_normal.png".into(),
+			anisotropy: 8.,
+			footstep_interval: 0.8,
+			auto_walk_speed: 2.,
+			auto_walk_turn_duration: 0.25,
+			auto_walk_reverse_bias: 0.001,
+			auto_walk_mode: AutoWalkMode::WallFollow,
+			auto_walk_hand: AutoWalkHand::Right,
 		}
 	}
 }
\ No newline at end of file
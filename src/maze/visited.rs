@@ -0,0 +1,111 @@
+use super::{
+	node_at_world_pos, node_to_world,
+	overhead::OverheadView,
+	solid_color::SolidColorAssets,
+	Chunk, ControlMode, CurrentChunk, Reset, CELL_SIZE,
+};
+use crate::prelude::*;
+use glam::vec3;
+use std::collections::HashSet;
+
+const TILE_HEIGHT: f32 = -CELL_SIZE / 2. + 0.01;
+
+/// Maze cells the player has entered, by chunk index and node index. Kept after chunks despawn
+/// and cleared when a new maze is generated.
+#[derive(Default)]
+pub struct VisitedCells(pub(super) HashSet<(usize, usize)>);
+
+impl VisitedCells {
+	pub fn contains(&self, chunk_index: usize, node: usize) -> bool {
+		self.0.contains(&(chunk_index, node))
+	}
+}
+
+pub struct VisitedAssets {
+	tile_mesh: Handle<Mesh>,
+}
+
+/// Floor tint for a maze cell, shown in the overhead view. A child of the chunk entity it
+/// holds, so it fades out and despawns together with the chunk.
+struct VisitedTile(Entity);
+
+pub fn load_visited_assets(mut cmd: Commands, mut meshes: ResMut<Assets<Mesh>>) {
+	cmd.insert_resource(VisitedAssets {
+		tile_mesh: meshes.add(Plane::new(CELL_SIZE, 1.).into()),
+	});
+}
+
+pub fn record_visited_cell(
+	mut visited: ResMut<VisitedCells>,
+	control_mode: Res<ControlMode>,
+	current_chunk: Res<CurrentChunk>,
+	q_chunks: Query<&Chunk>,
+	q_cam: Query<&GlobalTransform, With<Camera>>,
+) {
+	// the overhead camera floats above the chunk center
+	if *control_mode == ControlMode::Overhead {
+		return;
+	}
+	let chunk = match current_chunk.0.and_then(|e| q_chunks.get(e).ok()) {
+		Some(chunk) => chunk,
+		None => return,
+	};
+	let cam_pos = q_cam.single().unwrap().translation;
+	if let Some(node) = node_at_world_pos(chunk, cam_pos) {
+		visited.0.insert((chunk.index, node));
+	}
+}
+
+/// Tints the cells of the viewed chunk while the overhead view is active
+pub fn update_visited_tiles(
+	mut cmd: Commands,
+	overhead: Res<OverheadView>,
+	visited: Res<VisitedCells>,
+	assets: Res<VisitedAssets>,
+	solid_color: Res<SolidColorAssets>,
+	current_chunk: Res<CurrentChunk>,
+	mut q_chunks: Query<(&Chunk, &mut Children)>,
+	q_tiles: Query<(Entity, &VisitedTile)>,
+	mut shown: Local<bool>,
+) {
+	if overhead.is_active() == *shown {
+		return;
+	}
+	*shown = overhead.is_active();
+	if !*shown {
+		for (e, tile) in q_tiles.iter() {
+			if let Ok((_, mut chunk_children)) = q_chunks.get_mut(tile.0) {
+				chunk_children.0.retain(|child| *child != e);
+			}
+			cmd.entity(e).despawn();
+		}
+		return;
+	}
+	let chunk_entity = match current_chunk.0 {
+		Some(e) => e,
+		None => return,
+	};
+	let (chunk, mut chunk_children) = match q_chunks.get_mut(chunk_entity) {
+		Ok(found) => found,
+		Err(_) => return,
+	};
+	for node in chunk.maze.iter_nodes() {
+		let color = if visited.contains(chunk.index, node.idx()) {
+			vec3(0.3, 0.8, 0.4)
+		} else {
+			vec3(0.3, 0.3, 0.35)
+		};
+		let transform = GlobalTransform::from_translation(
+			node_to_world(node, chunk) + vec3(0., TILE_HEIGHT, 0.),
+		);
+		let tile = cmd
+			.spawn_bundle(solid_color.bundle(
+				assets.tile_mesh.clone(),
+				color,
+				transform.compute_matrix(),
+			))
+			.insert_bundle((VisitedTile(chunk_entity), transform, Reset))
+			.id();
+		chunk_children.0.push(tile);
+	}
+}
@@ -33,6 +33,16 @@ impl Distances {
 	pub fn insert(&mut self, node: GridNode, distance: i32) {
 		self.nodes.insert(node, distance);
 	}
+
+	/// returns the node farthest from the `root` and its distance. Ties go to the node with the
+	/// lowest index, so the result doesn't depend on the hashing order.
+	pub fn max(&self) -> (GridNode, i32) {
+		self.nodes
+			.iter()
+			.max_by_key(|(node, distance)| (**distance, std::cmp::Reverse(node.idx())))
+			.map(|(node, distance)| (*node, *distance))
+			.expect("root is always contained")
+	}
 }
 
 /// Allows indexing Distances using a `GridNode` struct and returning the distance of that
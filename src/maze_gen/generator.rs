@@ -1,6 +1,53 @@
-use super::{GridMaze, GridNode};
+use super::{GridDirection, GridMaze, GridNode};
 use rand::{Rng, seq::SliceRandom};
 
+/// Algorithms available to `generate_with`, each producing a perfect maze
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MazeAlgorithm {
+    /// Unbiased, see `generate`
+    Wilson,
+    /// Fast, with a horizontal bias and a fully open top row, see `sidewinder`
+    Sidewinder,
+    /// Fastest, with passages leading diagonally towards the `bias` corner, see `binary_tree`
+    BinaryTree { bias: Diagonal },
+}
+
+/// Corner of the maze a binary tree maze leads towards, its two edges become open corridors
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Diagonal {
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest,
+}
+
+impl Diagonal {
+    /// the vertical and horizontal direction pointing towards the corner
+    pub fn directions(self) -> (GridDirection, GridDirection) {
+        use GridDirection::*;
+        match self {
+            Diagonal::NorthEast => (Up, Right),
+            Diagonal::NorthWest => (Up, Left),
+            Diagonal::SouthEast => (Down, Right),
+            Diagonal::SouthWest => (Down, Left),
+        }
+    }
+}
+
+/// Generates a random maze of the given size with the chosen algorithm
+pub fn generate_with(
+    algorithm: MazeAlgorithm,
+    height: usize,
+    width: usize,
+    rng: &mut impl Rng,
+) -> GridMaze {
+    match algorithm {
+        MazeAlgorithm::Wilson => generate(height, width, rng),
+        MazeAlgorithm::Sidewinder => sidewinder(height, width, rng),
+        MazeAlgorithm::BinaryTree { bias } => binary_tree(height, width, bias, rng),
+    }
+}
+
 /// Generates a random maze using Wilson's algorithm:
 /// Like Aldous-Broder, this algorithm depends on the idea of a random walk, but with a twist.
 /// It performs what is called a loop-erased random walk, which means that as it goes, if the path
@@ -18,21 +65,20 @@ pub fn generate(height: usize, width: usize, rng: &mut impl Rng) -> GridMaze {
     // choose a random node in the maze, this will be the first visited node
     let first = maze.random_node(rng);
     // initialize unvisited to contain all positions in the maze except for first
-    let mut unvisited_nodes: Vec<GridNode> = maze
-        .iter_nodes()
-        .filter(|&node| *node != first)
-        .copied()
-        .collect();
+    let mut unvisited_nodes = UnvisitedNodes::new(maze.len(), first.idx());
+    // ranks of the unvisited nodes to pick from, only the first `unvisited_nodes.len` are used
+    let ranks: Vec<usize> = (0..maze.len()).collect();
 
     // repeat until all nodes have been visited
-    while !unvisited_nodes.is_empty() {
+    while unvisited_nodes.len > 0 {
         // choose a random, unvisited node and add it to the `path` that is about to be walked
-        let mut cur_node = *unvisited_nodes.choose(rng).unwrap();
+        let rank = *ranks[..unvisited_nodes.len].choose(rng).unwrap();
+        let mut cur_node = maze[unvisited_nodes.nth(rank)];
         // path contains the randomly walked nodes
         let mut path: Vec<GridNode> = vec![cur_node];
 
         // while the cur_node is a member of unvisited nodes
-        while unvisited_nodes.contains(&cur_node) {
+        while unvisited_nodes.contains(cur_node.idx()) {
             // choose a random neighbor of the current node
             cur_node = *maze
                 .neighbors(&cur_node)
@@ -41,7 +87,7 @@ pub fn generate(height: usize, width: usize, rng: &mut impl Rng) -> GridMaze {
 
             // if the random neighbor is already in path, there is a loop, so remove it
             if let Some(node_index) = path.iter().position(|node| *node == cur_node) {
-                path = path[0..=node_index].to_vec();
+                path.truncate(node_index + 1);
             } else {
                 // the random neigbor is not going to make a loop, so push it onto the path
                 path.push(cur_node);
@@ -52,13 +98,224 @@ pub fn generate(height: usize, width: usize, rng: &mut impl Rng) -> GridMaze {
         let mut window = path.windows(2);
         while let Some([node1, node2]) = window.next() {
             maze.link(node1, node2, true);
+            unvisited_nodes.remove(node1.idx());
+        }
+    }
+
+    debug_assert!(maze.is_perfect(), "generated maze is not a spanning tree");
+    maze
+}
+
+/// Nodes not yet visited by `generate`, by node index. Random picks count through the remaining
+/// nodes in index order, as they would in a vec the visited nodes are removed from, so a seed
+/// always produces the same maze. A Fenwick tree of the remaining nodes finds the picked one
+/// without shifting or swapping anything around.
+struct UnvisitedNodes {
+    unvisited: Vec<bool>,
+    /// `counts[i]` is the number of unvisited nodes among the `i & -i` indices up to `i - 1`
+    counts: Vec<usize>,
+    len: usize,
+}
+
+impl UnvisitedNodes {
+    /// all `node_count` nodes except for `visited`
+    fn new(node_count: usize, visited: usize) -> Self {
+        let mut unvisited = vec![true; node_count];
+        unvisited[visited] = false;
+        let mut counts = vec![0; node_count + 1];
+        for i in 1..=node_count {
+            counts[i] += unvisited[i - 1] as usize;
+            let parent = i + (i & i.wrapping_neg());
+            if parent <= node_count {
+                counts[parent] += counts[i];
+            }
+        }
+        Self {
+            unvisited,
+            counts,
+            len: node_count - 1,
+        }
+    }
+
+    fn contains(&self, idx: usize) -> bool {
+        self.unvisited[idx]
+    }
+
+    fn remove(&mut self, idx: usize) {
+        if !self.unvisited[idx] {
+            return;
+        }
+        self.unvisited[idx] = false;
+        self.len -= 1;
+        let mut i = idx + 1;
+        while i < self.counts.len() {
+            self.counts[i] -= 1;
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// index of the unvisited node with `rank` unvisited nodes before it
+    fn nth(&self, rank: usize) -> usize {
+        let mut idx = 0;
+        let mut remaining = rank;
+        let mut step = self.counts.len().next_power_of_two() / 2;
+        while step > 0 {
+            let next = idx + step;
+            if next < self.counts.len() && self.counts[next] <= remaining {
+                idx = next;
+                remaining -= self.counts[next];
+            }
+            step /= 2;
+        }
+        idx
+    }
+}
+
+/// Generates a random maze using the Sidewinder algorithm:
+/// Rows are processed one at a time, collecting a "run" of nodes linked to the east. At each
+/// node, the run either continues east or is closed by linking a random node of the run to the
+/// north. The top row has nothing to the north, so it always continues east, leaving a single
+/// open corridor along the top of the maze.
+pub fn sidewinder(height: usize, width: usize, rng: &mut impl Rng) -> GridMaze {
+    let mut maze = GridMaze::new(height, width);
+    let rows = maze
+        .iter_rows()
+        .map(|row| row.to_vec())
+        .collect::<Vec<Vec<GridNode>>>();
 
-            // remove the nodes in the path from the vector of unvisited nodes
-            if let Some(path_idx) = unvisited_nodes.iter().position(|node| *node == *node1) {
-                unvisited_nodes.remove(path_idx);
+    for row in rows.iter() {
+        // run holds the nodes linked east since the last link to the north
+        let mut run: Vec<GridNode> = vec![];
+        for node in row.iter() {
+            run.push(*node);
+            let at_east_edge = maze.right(node).is_none();
+            let at_top_edge = maze.up(node).is_none();
+            let close_run = at_east_edge || (!at_top_edge && rng.gen_bool(0.5));
+            if close_run {
+                let member = *run.choose(rng).unwrap();
+                if let Some(north) = maze.up(&member) {
+                    maze.link(&member, &north, true);
+                }
+                run.clear();
+            } else {
+                let east = maze.right(node).unwrap();
+                maze.link(node, &east, true);
             }
         }
     }
 
+    debug_assert!(maze.is_perfect(), "generated maze is not a spanning tree");
+    maze
+}
+
+/// Generates a random maze using the Binary Tree algorithm:
+/// Each node is linked to its neighbor in one of the two directions of the `bias` corner, chosen
+/// at random. Nodes along the edges of that corner only have one of the neighbors, so both edges
+/// become straight corridors, and the corner node itself links nowhere.
+pub fn binary_tree(height: usize, width: usize, bias: Diagonal, rng: &mut impl Rng) -> GridMaze {
+    let mut maze = GridMaze::new(height, width);
+    let (vertical, horizontal) = bias.directions();
+    let nodes = maze.iter_nodes().copied().collect::<Vec<GridNode>>();
+
+    for node in nodes.iter() {
+        let candidates = [vertical, horizontal]
+            .iter()
+            .filter_map(|dir| maze.get_neighbor(node, *dir))
+            .collect::<Vec<GridNode>>();
+        if let Some(neighbor) = candidates.choose(rng) {
+            maze.link(node, neighbor, true);
+        }
+    }
+
+    debug_assert!(maze.is_fully_connected(), "generated maze is not connected");
     maze
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze_gen::GridDirection;
+    use rand::{rngs::SmallRng, SeedableRng};
+
+    #[test]
+    fn sidewinder_makes_perfect_maze_with_open_top_row() {
+        let mut rng = SmallRng::seed_from_u64(3);
+        for _ in 0..10 {
+            let maze = generate_with(MazeAlgorithm::Sidewinder, 6, 9, &mut rng);
+            assert!(maze.is_perfect());
+            let top_row = maze.iter_rows().next().unwrap();
+            for node in &top_row[..top_row.len() - 1] {
+                assert!(maze.has_link(node, GridDirection::Right));
+            }
+        }
+    }
+
+    #[test]
+    fn binary_tree_opens_both_edges_of_bias_corner() {
+        let mut rng = SmallRng::seed_from_u64(5);
+        let corners = [
+            Diagonal::NorthEast,
+            Diagonal::NorthWest,
+            Diagonal::SouthEast,
+            Diagonal::SouthWest,
+        ];
+        for bias in corners.iter().copied() {
+            let maze = generate_with(MazeAlgorithm::BinaryTree { bias }, 5, 7, &mut rng);
+            assert!(maze.is_fully_connected());
+            assert!(maze.is_perfect());
+            let (vertical, horizontal) = bias.directions();
+            // the edge facing `vertical` is a corridor running along `horizontal`, and vice versa
+            for (edge, along) in [(vertical, horizontal), (horizontal, vertical)].iter() {
+                for node in maze.get_edge_nodes(*edge) {
+                    let has_next = maze.get_neighbor(&node, *along).is_some();
+                    assert_eq!(maze.has_link(&node, *along), has_next, "{:?} {:?}", bias, edge);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn wilson_is_deterministic_for_seed() {
+        let maze = generate(16, 16, &mut SmallRng::seed_from_u64(11));
+        let same_seed = generate(16, 16, &mut SmallRng::seed_from_u64(11));
+        assert!(maze.is_perfect());
+        assert_eq!(maze.to_bytes(), same_seed.to_bytes());
+
+        // recorded with the generator that removed visited nodes from a vec one at a time
+        let golden = [
+            8, 0, 0, 0, 8, 0, 0, 0, 83, 147, 90, 149, 103, 143, 67, 186, 153, 34, 166, 179, 31,
+            35, 84, 21,
+        ];
+        assert_eq!(generate(8, 8, &mut SmallRng::seed_from_u64(11)).to_bytes(), golden);
+    }
+
+    /// Catches accidental quadratic slowdowns and shows how the algorithms compare.
+    /// Run with `cargo test --release -- --ignored --nocapture generation_benchmark`
+    #[test]
+    #[ignore]
+    fn generation_benchmark() {
+        const SIZE: usize = 64;
+        const RUNS: u32 = 5;
+        let algorithms = vec![
+            MazeAlgorithm::Wilson,
+            MazeAlgorithm::Sidewinder,
+            MazeAlgorithm::BinaryTree { bias: Diagonal::NorthEast },
+        ];
+        for algorithm in algorithms {
+            let mut rng = SmallRng::seed_from_u64(7);
+            let start = std::time::Instant::now();
+            for _ in 0..RUNS {
+                let maze = generate_with(algorithm, SIZE, SIZE, &mut rng);
+                assert!(maze.is_fully_connected(), "{:?}", algorithm);
+                assert!(maze.is_perfect(), "{:?}", algorithm);
+            }
+            println!(
+                "{:?}: {:?} per {}x{} maze",
+                algorithm,
+                start.elapsed() / RUNS,
+                SIZE,
+                SIZE
+            );
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use super::{distances::Distances, GridNode};
+use glam::Vec2;
 use rand::{Rng, seq::SliceRandom};
-use std::collections::HashMap;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fmt::{Display, Formatter};
 use std::ops::Index;
 use std::slice::{ChunksExact, Iter, IterMut};
@@ -93,6 +95,25 @@ impl GridMaze {
 		}
 	}
 
+	/// removes the link between the two nodes, closing the passageway between them.
+	/// `bi_link` also removes the link from node2 => node1 if it is `true`. Nodes left without
+	/// links are removed from the link map
+	pub fn unlink(&mut self, node1: &GridNode, node2: &GridNode, bi_link: bool) {
+		self.remove_link(node1.idx(), node2.idx());
+		if bi_link {
+			self.remove_link(node2.idx(), node1.idx());
+		}
+	}
+
+	fn remove_link(&mut self, from: usize, to: usize) {
+		if let Some(node_links) = self.links.get_mut(&from) {
+			node_links.retain(|idx| *idx != to);
+			if node_links.is_empty() {
+				self.links.remove(&from);
+			}
+		}
+	}
+
 	// returns copies of GridNode(s) that the given `node` links to.
 	// In this particular maze, each node can have at most 4 links, or edges, to another Node
 	// If the given node doesn't link to anything, an empty Vector is returned
@@ -143,6 +164,14 @@ impl GridMaze {
 		neighbors.into_iter().flatten().collect()
 	}
 
+	/// returns the `neighbors` of the given `node` shuffled with `rng`, for generators that try
+	/// neighbors in random order
+	pub fn shuffled_neighbors(&self, node: &GridNode, rng: &mut impl Rng) -> Vec<GridNode> {
+		let mut neighbors = self.neighbors(node);
+		neighbors.shuffle(rng);
+		neighbors
+	}
+
 	/// returns a copy of a random node in the maze
 	pub fn random_node(&self, rng: &mut impl Rng) -> GridNode {
 		let rand_idx = rng.gen_range(0..self.nodes.len());
@@ -154,6 +183,12 @@ impl GridMaze {
 		self.nodes.chunks_exact(self.cols)
 	}
 
+	/// returns an immutable iterator over the *columns* of this maze, each gathered from the
+	/// row-major node storage by stepping one row at a time
+	pub fn iter_cols(&self) -> impl Iterator<Item = Vec<&GridNode>> + '_ {
+		(0..self.cols).map(move |col| self.nodes.iter().skip(col).step_by(self.cols).collect())
+	}
+
 	/// returns an immutable iterator over this maze's Nodes in row order
 	pub fn iter_nodes(&self) -> Iter<'_, GridNode> {
 		self.nodes.iter()
@@ -265,6 +300,142 @@ impl Display for GridMaze {
 	}
 }
 
+/// Functions for (de)serializing a GridMaze
+impl GridMaze {
+	/// parses a maze from the ASCII format emitted by the `Display` impl. Rows and columns are
+	/// inferred from the grid, and a bi-directional link is created wherever a wall is absent
+	pub fn from_ascii(s: &str) -> Result<GridMaze, String> {
+		const SEGMENT_LEN: usize = 5;
+		if !s.is_ascii() {
+			return Err("maze contains non-ASCII characters".into());
+		}
+		let lines = s.lines().collect::<Vec<_>>();
+		let top_border = lines.first().ok_or("empty maze")?;
+		let line_len = top_border.len();
+		if line_len < 1 + SEGMENT_LEN || (line_len - 1) % SEGMENT_LEN != 0 {
+			return Err(format!("malformed top border: {:?}", top_border));
+		}
+		let cols = (line_len - 1) / SEGMENT_LEN;
+		if *top_border != format!("+{}", "----+".repeat(cols)) {
+			return Err(format!("malformed top border: {:?}", top_border));
+		}
+		if lines.len() < 3 || lines.len() % 2 == 0 {
+			return Err(format!("unexpected number of lines: {}", lines.len()));
+		}
+		let rows = (lines.len() - 1) / 2;
+
+		let mut maze = GridMaze::new(rows, cols);
+		for row in 0..rows {
+			let (top, bottom) = (lines[row * 2 + 1], lines[row * 2 + 2]);
+			for (line_no, line) in [(row * 2 + 1, top), (row * 2 + 2, bottom)].iter() {
+				if line.len() != line_len {
+					return Err(format!("ragged line {}: {:?}", line_no, line));
+				}
+			}
+			if !top.starts_with('|') || !bottom.starts_with('+') {
+				return Err(format!("malformed left border in row {}", row));
+			}
+			for col in 0..cols {
+				let node = maze[Self::idx_1d(row, col, cols)];
+				let range = 1 + col * SEGMENT_LEN..1 + (col + 1) * SEGMENT_LEN;
+				let right_open = match &top[range.clone()] {
+					"    |" => false,
+					"     " if col + 1 < cols => true,
+					segment => {
+						return Err(format!("malformed cell {:?} at {},{}", segment, row, col));
+					}
+				};
+				let down_open = match &bottom[range] {
+					"----+" => false,
+					"    +" if row + 1 < rows => true,
+					segment => {
+						return Err(format!("malformed wall {:?} at {},{}", segment, row, col));
+					}
+				};
+				if right_open {
+					let neighbor = maze[node.idx() + 1];
+					maze.link(&node, &neighbor, true);
+				}
+				if down_open {
+					let neighbor = maze[node.idx() + cols];
+					maze.link(&node, &neighbor, true);
+				}
+			}
+		}
+		Ok(maze)
+	}
+}
+
+/// Functions for (de)serializing a GridMaze
+///
+/// The byte format is the row and column counts as little-endian u32 values, followed by two bits
+/// per node in row-order: a link to the right neighbor, then a link to the down neighbor.
+/// Links are always restored as bi-directional links, and node weights are not stored.
+impl GridMaze {
+	const HEADER_LEN: usize = 8;
+
+	/// encodes this maze into a compact byte representation, see `from_bytes`
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut bytes = Vec::with_capacity(Self::HEADER_LEN + Self::link_bytes_len(self.len()));
+		bytes.extend_from_slice(&(self.rows as u32).to_le_bytes());
+		bytes.extend_from_slice(&(self.cols as u32).to_le_bytes());
+		let mut links = vec![0u8; Self::link_bytes_len(self.len())];
+		for node in self.nodes.iter() {
+			for (bit, dir) in [GridDirection::Right, GridDirection::Down].iter().enumerate() {
+				if self.has_link(node, *dir) {
+					let bit_idx = node.idx() * 2 + bit;
+					links[bit_idx / 8] |= 1 << (bit_idx % 8);
+				}
+			}
+		}
+		bytes.extend(links);
+		bytes
+	}
+
+	/// decodes a maze encoded with `to_bytes`
+	pub fn from_bytes(bytes: &[u8]) -> Result<GridMaze, String> {
+		if bytes.len() < Self::HEADER_LEN {
+			return Err(format!("maze data too short: {} bytes", bytes.len()));
+		}
+		let read_u32 = |offset: usize| {
+			let mut buf = [0u8; 4];
+			buf.copy_from_slice(&bytes[offset..offset + 4]);
+			u32::from_le_bytes(buf) as usize
+		};
+		let (rows, cols) = (read_u32(0), read_u32(4));
+		let node_count = rows
+			.checked_mul(cols)
+			.ok_or_else(|| format!("invalid maze dimensions: {}x{}", rows, cols))?;
+		let expected_len = Self::HEADER_LEN + Self::link_bytes_len(node_count);
+		if bytes.len() != expected_len {
+			return Err(format!(
+				"maze data length mismatch: expected {} bytes, got {}",
+				expected_len,
+				bytes.len()
+			));
+		}
+		let links = &bytes[Self::HEADER_LEN..];
+		let mut maze = GridMaze::new(rows, cols);
+		for idx in 0..node_count {
+			let node = maze[idx];
+			for (bit, dir) in [GridDirection::Right, GridDirection::Down].iter().enumerate() {
+				let bit_idx = idx * 2 + bit;
+				if links[bit_idx / 8] & (1 << (bit_idx % 8)) != 0 {
+					let neighbor = maze
+						.get_neighbor(&node, *dir)
+						.ok_or_else(|| format!("node {} links {:?} out of the maze", idx, dir))?;
+					maze.link(&node, &neighbor, true);
+				}
+			}
+		}
+		Ok(maze)
+	}
+
+	fn link_bytes_len(node_count: usize) -> usize {
+		(node_count * 2 + 7) / 8
+	}
+}
+
 /// Functions for converting a GridMaze into a Braided Maze
 ///
 impl GridMaze {
@@ -278,6 +449,25 @@ impl GridMaze {
 			.collect()
 	}
 
+	/// returns the number of passages carved between nodes. A bi-directional link is stored once
+	/// from each end, and only counts as one passage
+	pub fn passage_count(&self) -> usize {
+		self.links
+			.iter()
+			.flat_map(|(&from, links)| links.iter().map(move |&to| (from, to)))
+			.filter(|&(from, to)| {
+				let linked_back = self.links.get(&to).map_or(false, |l| l.contains(&from));
+				from < to || !linked_back
+			})
+			.count()
+	}
+
+	/// returns the number of branch points in the maze, nodes with three or more links where the
+	/// path splits
+	pub fn branch_points(&self) -> usize {
+		self.links.values().filter(|links| links.len() >= 3).count()
+	}
+
 	/// Adds braids to this maze by removing dead-end nodes and turning them into loops
 	///
 	/// `p` - is a value between 0.0 and 1.0 and is the percentage amount of dead-ends to remove.
@@ -321,6 +511,68 @@ impl GridMaze {
 	}
 }
 
+/// Functions for assigning movement costs to the nodes of a maze
+impl GridMaze {
+	/// sets the cost of moving into the node at `idx`, used by `distances` and `path_to`
+	pub fn set_weight(&mut self, idx: usize, weight: isize) {
+		self.nodes[idx].set_weight(weight);
+	}
+
+	/// sets the weight of all nodes within `count` randomly placed square regions to `weight`,
+	/// making paths through them more costly. Each region spans `radius` nodes around its center.
+	pub fn add_weighted_regions(
+		&mut self,
+		count: usize,
+		radius: i32,
+		weight: isize,
+		rng: &mut impl Rng,
+	) {
+		for _ in 0..count {
+			let (center_x, center_y) = self.idx_to_pos(self.random_node(rng).idx());
+			for y in center_y - radius..=center_y + radius {
+				for x in center_x - radius..=center_x + radius {
+					if let Some(idx) = self.pos_to_idx((x, y)) {
+						self.set_weight(idx, weight);
+					}
+				}
+			}
+		}
+	}
+}
+
+/// Functions for validating the structure of a maze
+impl GridMaze {
+	/// returns the indices of all nodes that can be reached from `node` by following links,
+	/// including `node` itself. Unlike `distances`, ignores node weights.
+	pub fn reachable_from(&self, node: &GridNode) -> HashSet<usize> {
+		let mut reached = HashSet::new();
+		reached.insert(node.idx());
+		let mut pending = vec![*node];
+		while let Some(cur_node) = pending.pop() {
+			for neighbor in self.get_links(&cur_node) {
+				if reached.insert(neighbor.idx()) {
+					pending.push(neighbor);
+				}
+			}
+		}
+		reached
+	}
+
+	/// returns `true` if every node of the maze can be reached from the first node
+	pub fn is_fully_connected(&self) -> bool {
+		match self.nodes.first() {
+			Some(first) => self.reachable_from(first).len() == self.len(),
+			None => true,
+		}
+	}
+
+	/// returns `true` if the maze is a spanning tree: fully connected and without loops, so there is
+	/// exactly one path between any two nodes
+	pub fn is_perfect(&self) -> bool {
+		self.passage_count() + 1 == self.len() && self.is_fully_connected()
+	}
+}
+
 /// Functions to compute distances between nodes of a maze
 impl GridMaze {
 	/// find the distances from a `root` node to all other nodes in this `maze`, using each node's
@@ -334,11 +586,10 @@ impl GridMaze {
 		let mut pending = vec![*root];
 
 		while !pending.is_empty() {
-			// sort pending so that cells with lowest weight are at the **end** of pending
-			pending
-				.sort_unstable_by(|&an, &bn| self[bn.idx()].weight().cmp(&self[an.idx()].weight()));
+			// sort pending so that cells with the lowest total cost are at the **end** of pending
+			pending.sort_unstable_by_key(|&n| std::cmp::Reverse(weights[n]));
 
-			// pop the last position from pending, it will have the lowest weight
+			// pop the last position from pending, it will have the lowest total cost
 			let cur_node = pending.pop().unwrap();
 
 			// iterate thru the linked neighbors and compute the cost of moving into
@@ -362,6 +613,43 @@ impl GridMaze {
 		weights
 	}
 
+	/// find the shortest path from the `from` node to the `to` node, by walking back from `to`
+	/// through the distances computed from `from`.
+	/// returns the nodes of the path, including both ends. Returns an empty Vec if `to` cannot
+	/// be reached.
+	pub fn path_to(&self, from: &GridNode, to: &GridNode) -> Vec<GridNode> {
+		let distances = self.distances(from);
+		if distances.get(to).is_none() {
+			return vec![];
+		}
+		let mut path = vec![*to];
+		let mut current = *to;
+		while current != *from {
+			// the previous node is the linked neighbor the current node was reached from
+			let current_distance = distances[current];
+			current = self
+				.get_links(&current)
+				.into_iter()
+				.find(|n| {
+					distances.get(n).copied()
+						== Some(current_distance - self[current.idx()].weight() as i32)
+				})
+				.expect("previous node on path");
+			path.push(current);
+		}
+		path.reverse();
+		path
+	}
+
+	/// find the two nodes farthest apart in this maze, by finding the node farthest from any
+	/// node, then the node farthest from that one. Exact for perfect mazes.
+	/// returns both end nodes and the path between them.
+	pub fn longest_path(&self) -> (GridNode, GridNode, Vec<GridNode>) {
+		let (start, _) = self.distances(&self[0]).max();
+		let (end, _) = self.distances(&start).max();
+		(start, end, self.path_to(&start, &end))
+	}
+
 	/// pretty prints the `maze` and also displays each cell of `path` within its corresponding
 	/// GridCell by printing its weight as a hexadecimal value.
 	pub fn display_path(&self, path: &Distances) -> String {
@@ -406,7 +694,7 @@ impl GridMaze {
 	}
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum GridDirection {
 	Up,
 	Right,
@@ -452,6 +740,12 @@ impl GridDirection {
 			GridDirection::Right => GridDirection::Up,
 		}
 	}
+
+	/// The grid offset as a vector, with Up pointing towards negative Y
+	pub fn to_vec2(self) -> Vec2 {
+		let (x, y) = self.get_offset();
+		Vec2::new(x as f32, y as f32)
+	}
 }
 
 pub trait WorldDirections {
@@ -477,7 +771,8 @@ impl WorldDirections for GridMaze {
 
 #[cfg(test)]
 mod tests {
-	use super::GridMaze;
+	use super::{GridDirection, GridMaze};
+	use std::collections::HashSet;
 
 	#[test]
 	fn create_new_maze_with_9_nodes() {
@@ -513,6 +808,26 @@ mod tests {
 		assert!(maze.links.get(&n2.idx()).unwrap().contains(&n1.idx()));
 	}
 
+	#[test]
+	fn should_bi_unlink_two_nodes() {
+		let mut maze = GridMaze::new(3, 3);
+		let n1 = maze[0];
+		let n2 = maze[1];
+		let n3 = maze[3];
+		maze.link(&n1, &n2, true);
+		maze.link(&n1, &n3, true);
+		maze.unlink(&n1, &n2, true);
+		assert!(!maze.has_node_link(&n1, &n2));
+		assert!(!maze.has_node_link(&n2, &n1));
+		assert!(!maze.links.contains_key(&n2.idx()));
+		assert!(maze.has_node_link(&n1, &n3));
+
+		maze.link(&n1, &n2, true);
+		maze.unlink(&n1, &n2, false);
+		assert!(!maze.has_node_link(&n1, &n2));
+		assert!(maze.has_node_link(&n2, &n1));
+	}
+
 	#[test]
 	fn should_get_links() {
 		let mut maze = GridMaze::new(3, 3);
@@ -575,6 +890,18 @@ mod tests {
 		assert_eq!(maze.right(&node), None);
 	}
 
+	#[test]
+	fn should_iterate_columns_top_to_bottom() {
+		let maze = GridMaze::new(3, 4);
+		let cols = maze
+			.iter_cols()
+			.map(|col| col.iter().map(|n| n.idx()).collect::<Vec<_>>())
+			.collect::<Vec<_>>();
+		assert_eq!(cols.len(), 4);
+		assert_eq!(cols[0], vec![0, 4, 8]);
+		assert_eq!(cols[3], vec![3, 7, 11]);
+	}
+
 	#[test]
 	fn node_4_should_have_all_neighbors() {
 		let maze = GridMaze::new(3, 3);
@@ -585,6 +912,24 @@ mod tests {
 		assert_eq!(maze.down(&node), Some(maze[7]));
 	}
 
+	#[test]
+	fn should_shuffle_neighbors_with_rng() {
+		use rand::{rngs::SmallRng, SeedableRng};
+		let maze = GridMaze::new(3, 3);
+		let shuffled = |node: usize, seed: u64| {
+			let mut rng = SmallRng::seed_from_u64(seed);
+			maze.shuffled_neighbors(&maze[node], &mut rng)
+		};
+		for node in 0..maze.len() {
+			assert_eq!(shuffled(node, 5), shuffled(node, 5));
+			let mut neighbors = maze.neighbors(&maze[node]);
+			let mut shuffled = shuffled(node, 5);
+			neighbors.sort_by_key(|n| n.idx());
+			shuffled.sort_by_key(|n| n.idx());
+			assert_eq!(shuffled, neighbors);
+		}
+	}
+
 	#[test]
 	fn should_display_maze_with_link_from_00_to_01() {
 		let mut maze = GridMaze::new(4, 4);
@@ -594,6 +939,153 @@ mod tests {
 		println!("{}", &maze);
 	}
 
+	#[test]
+	fn should_find_path_between_linked_nodes() {
+		let mut maze = GridMaze::new(3, 3);
+		let (n0, n1, n2, n5) = (maze[0], maze[1], maze[2], maze[5]);
+		maze.link(&n0, &n1, true);
+		maze.link(&n1, &n2, true);
+		maze.link(&n2, &n5, true);
+		assert_eq!(maze.path_to(&n0, &n5), vec![n0, n1, n2, n5]);
+		assert_eq!(maze.path_to(&n0, &n0), vec![n0]);
+		assert!(maze.path_to(&n0, &maze[8]).is_empty());
+	}
+
+	#[test]
+	fn should_route_around_high_weight_node() {
+		// a 2x2 loop with two equally long routes from n0 to n3
+		let mut maze = GridMaze::new(2, 2);
+		let (n0, n1, n2, n3) = (maze[0], maze[1], maze[2], maze[3]);
+		maze.link(&n0, &n1, true);
+		maze.link(&n1, &n3, true);
+		maze.link(&n0, &n2, true);
+		maze.link(&n2, &n3, true);
+		maze.set_weight(1, 10);
+		assert_eq!(maze.path_to(&n0, &n3), vec![n0, n2, n3]);
+		assert_eq!(maze.distances(&n0)[n3], 2);
+		maze.set_weight(1, 1);
+		maze.set_weight(2, 10);
+		assert_eq!(maze.path_to(&n0, &n3), vec![n0, n1, n3]);
+	}
+
+	#[test]
+	fn should_find_longest_path_between_branch_ends() {
+		#[rustfmt::skip]
+		let maze = GridMaze::from_ascii(&[
+			"+----+----+----+",
+			"|    |         |",
+			"+    +    +----+",
+			"|              |",
+			"+----+----+----+",
+		].join("\n")).unwrap();
+		let (start, end, path) = maze.longest_path();
+		let mut ends = [start.idx(), end.idx()];
+		ends.sort_unstable();
+		// from the top left dead end down and around to the top right one
+		assert_eq!(ends, [0, 2]);
+		assert_eq!(path.first(), Some(&start));
+		assert_eq!(path.last(), Some(&end));
+		assert_eq!(path.len(), 5);
+		assert_eq!(maze.distances(&start)[end], 4);
+	}
+
+	#[test]
+	fn should_count_passages_and_branch_points() {
+		#[rustfmt::skip]
+		let mut maze = GridMaze::from_ascii(&[
+			"+----+----+----+",
+			"|         |    |",
+			"+    +    +    +",
+			"|              |",
+			"+    +    +    +",
+			"|    |         |",
+			"+----+----+----+",
+		].join("\n")).unwrap();
+		assert_eq!(maze.passage_count(), 10);
+		// the middle row, with the center node open on all sides
+		assert_eq!(maze.branch_points(), 3);
+		let (n6, n7) = (maze[6], maze[7]);
+		maze.link(&n6, &n7, false);
+		assert_eq!(maze.passage_count(), 11);
+		assert_eq!(maze.branch_points(), 3);
+		maze.link(&n7, &n6, false);
+		assert_eq!(maze.passage_count(), 11);
+		assert_eq!(maze.branch_points(), 4);
+
+		use rand::{rngs::SmallRng, SeedableRng};
+		let mut rng = SmallRng::seed_from_u64(6);
+		let perfect = crate::maze_gen::generate(6, 6, &mut rng);
+		assert_eq!(perfect.passage_count(), perfect.len() - 1);
+	}
+
+	#[test]
+	fn should_find_nodes_reachable_from_a_node() {
+		#[rustfmt::skip]
+		let mut maze = GridMaze::from_ascii(&[
+			"+----+----+----+",
+			"|         |    |",
+			"+----+----+    +",
+			"|    |         |",
+			"+----+----+----+",
+		].join("\n")).unwrap();
+		let component = |indices: &[usize]| indices.iter().copied().collect::<HashSet<_>>();
+		assert_eq!(maze.reachable_from(&maze[0]), component(&[0, 1]));
+		assert_eq!(maze.reachable_from(&maze[5]), component(&[2, 4, 5]));
+		assert_eq!(maze.reachable_from(&maze[3]), component(&[3]));
+		// a one-way link only reaches onwards
+		let (n1, n4) = (maze[1], maze[4]);
+		maze.link(&n1, &n4, false);
+		assert_eq!(maze.reachable_from(&maze[0]), component(&[0, 1, 2, 4, 5]));
+		assert_eq!(maze.reachable_from(&maze[4]), component(&[2, 4, 5]));
+	}
+
+	#[test]
+	fn should_round_trip_through_bytes() {
+		use rand::{rngs::SmallRng, SeedableRng};
+		let mut rng = SmallRng::seed_from_u64(1);
+		let mut maze = crate::maze_gen::generate(5, 7, &mut rng);
+		maze.braid(0.5, &mut rng);
+		let restored = GridMaze::from_bytes(&maze.to_bytes()).unwrap();
+		assert_eq!(restored.dimensions(), maze.dimensions());
+		assert_eq!(format!("{}", restored), format!("{}", maze));
+		for node in maze.iter_nodes() {
+			let sorted_links = |m: &GridMaze| {
+				let mut links = m.get_links(node).iter().map(|n| n.idx()).collect::<Vec<_>>();
+				links.sort_unstable();
+				links
+			};
+			assert_eq!(sorted_links(&restored), sorted_links(&maze));
+		}
+	}
+
+	#[test]
+	fn should_round_trip_through_ascii() {
+		use rand::{rngs::SmallRng, SeedableRng};
+		let mut rng = SmallRng::seed_from_u64(2);
+		let mut maze = crate::maze_gen::generate(4, 6, &mut rng);
+		maze.braid(0.5, &mut rng);
+		let restored = GridMaze::from_ascii(&maze.to_string()).unwrap();
+		assert_eq!(restored.dimensions(), maze.dimensions());
+		assert_eq!(restored.to_string(), maze.to_string());
+	}
+
+	#[test]
+	fn should_reject_malformed_ascii() {
+		let ascii = GridMaze::new(2, 2).to_string();
+		let mut ragged = ascii.lines().map(String::from).collect::<Vec<_>>();
+		ragged[1].push(' ');
+		assert!(GridMaze::from_ascii(&ragged.join("\n")).is_err());
+		assert!(GridMaze::from_ascii(&ascii.replacen('+', "#", 1)).is_err());
+		assert!(GridMaze::from_ascii("").is_err());
+	}
+
+	#[test]
+	fn should_reject_truncated_bytes() {
+		let bytes = GridMaze::new(3, 3).to_bytes();
+		assert!(GridMaze::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+		assert!(GridMaze::from_bytes(&bytes[..4]).is_err());
+	}
+
 	#[test]
 	fn directions_map_to_correct_nodes() {
 		use super::GridDirection::*;
@@ -666,4 +1158,63 @@ mod tests {
 		assert_eq!(pti((2, 2)), 8);
 		assert_eq!(maze.pos_to_idx((3, 0)), None);
 	}
+
+	#[test]
+	fn generated_maze_is_perfect() {
+		use rand::{rngs::SmallRng, SeedableRng};
+		let mut rng = SmallRng::seed_from_u64(3);
+		for _ in 0..10 {
+			let maze = crate::maze_gen::generate(8, 8, &mut rng);
+			assert!(maze.is_fully_connected());
+			assert!(maze.is_perfect());
+		}
+	}
+
+	#[test]
+	fn braided_maze_is_connected_but_not_perfect() {
+		use rand::{rngs::SmallRng, SeedableRng};
+		let mut rng = SmallRng::seed_from_u64(4);
+		let mut maze = crate::maze_gen::generate(8, 8, &mut rng);
+		maze.braid(1.0, &mut rng);
+		assert!(maze.is_fully_connected());
+		assert!(!maze.is_perfect());
+	}
+
+	#[test]
+	fn disconnected_maze_is_not_perfect() {
+		let mut maze = GridMaze::new(2, 2);
+		let (n0, n1, n2) = (maze[0], maze[1], maze[2]);
+		maze.link(&n0, &n1, true);
+		maze.link(&n0, &n2, true);
+		assert!(!maze.is_fully_connected());
+		assert!(!maze.is_perfect());
+		let n3 = maze[3];
+		maze.link(&n1, &n3, true);
+		assert!(maze.is_perfect());
+	}
+
+	#[test]
+	fn opposite_of_opposite_is_same_direction() {
+		for d in GridDirection::ALL.iter().copied() {
+			assert_ne!(d.opposite(), d);
+			assert_eq!(d.opposite().opposite(), d);
+		}
+	}
+
+	#[test]
+	fn four_rotations_return_to_start() {
+		for d in GridDirection::ALL.iter().copied() {
+			assert_eq!(d.rotate_cw().rotate_cw().rotate_cw().rotate_cw(), d);
+			assert_eq!(d.rotate_ccw().rotate_ccw().rotate_ccw().rotate_ccw(), d);
+			assert_eq!(d.rotate_cw().rotate_ccw(), d);
+			assert_eq!(d.rotate_cw().rotate_cw(), d.opposite());
+		}
+	}
+
+	#[test]
+	fn direction_vectors_match_offsets() {
+		assert_eq!(GridDirection::Up.to_vec2(), glam::vec2(0., -1.));
+		assert_eq!(GridDirection::Right.to_vec2(), glam::vec2(1., 0.));
+		assert_eq!(GridDirection::Up.rotate_cw(), GridDirection::Right);
+	}
 }
@@ -4,9 +4,12 @@ mod distances;
 mod generator;
 mod grid_maze;
 mod grid_node;
+mod world;
 
 pub use {
-	generator::generate,
+	distances::{overlay_distances, Distances},
+	generator::{binary_tree, generate, generate_with, sidewinder, Diagonal, MazeAlgorithm},
 	grid_maze::{GridMaze, GridDirection},
 	grid_node::GridNode,
+	world::{world_to_grid, TupleVecConversion},
 };
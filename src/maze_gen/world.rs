@@ -0,0 +1,91 @@
+//! Conversions between grid coordinates and world space. Grid X maps to world X and grid Y to
+//! world Z, so `GridDirection::Up` (towards negative Y) faces world forward (negative Z).
+
+use super::GridDirection;
+use glam::{vec3, IVec2, Mat4, Quat, Vec2, Vec3};
+
+pub trait TupleVecConversion {
+	fn to_vec2(self) -> Vec2;
+	/// The grid position on the XZ plane
+	fn to_vec3(self) -> Vec3;
+	fn to_ivec2(self) -> IVec2;
+	/// Rotates the +Z axis to point along the grid offset on the XZ plane
+	fn to_mat4(self) -> Mat4;
+}
+
+impl TupleVecConversion for (i32, i32) {
+	fn to_vec2(self) -> Vec2 {
+		Vec2::new(self.0 as f32, self.1 as f32)
+	}
+
+	fn to_vec3(self) -> Vec3 {
+		Vec3::new(self.0 as f32, 0., self.1 as f32)
+	}
+
+	fn to_ivec2(self) -> IVec2 {
+		IVec2::new(self.0, self.1)
+	}
+
+	fn to_mat4(self) -> Mat4 {
+		// look_at_rh builds the inverse (view) rotation, the flipped Z turns it back around
+		Mat4::look_at_rh(Vec3::ZERO, self.to_vec3() * vec3(1., 1., -1.), Vec3::Y)
+	}
+}
+
+/// The grid cell containing a world position, with cells centered on whole coordinates
+pub fn world_to_grid(pos: Vec3) -> (i32, i32) {
+	(pos.x.round() as i32, pos.z.round() as i32)
+}
+
+impl GridDirection {
+	pub fn to_ivec2(self) -> IVec2 {
+		self.get_offset().to_ivec2()
+	}
+
+	/// The grid offset as a unit vector on the XZ plane
+	pub fn to_vec3(self) -> Vec3 {
+		self.get_offset().to_vec3()
+	}
+
+	/// Rotates the +Z axis to point in this direction, see `TupleVecConversion::to_mat4`
+	pub fn to_mat4(self) -> Mat4 {
+		self.get_offset().to_mat4()
+	}
+
+	/// Yaw rotation turning world forward (negative Z) to face this direction
+	pub fn to_rotation(self) -> Quat {
+		Quat::from_rotation_y(Vec2::Y.angle_between(self.to_vec2() * Vec2::new(1., -1.)))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn assert_near(a: Vec3, b: Vec3) {
+		assert!(a.abs_diff_eq(b, 1e-5), "{} != {}", a, b);
+	}
+
+	#[test]
+	fn directions_in_world_space() {
+		use GridDirection::*;
+		assert_eq!(Up.to_vec3(), vec3(0., 0., -1.));
+		assert_eq!(Right.to_vec3(), vec3(1., 0., 0.));
+		assert_eq!(Down.to_ivec2(), IVec2::new(0, 1));
+		for dir in GridDirection::ALL.iter() {
+			assert_near(dir.to_rotation() * -Vec3::Z, dir.to_vec3());
+			// wall faces are placed half a cell along +Z before being rotated
+			assert_near(dir.to_mat4().transform_point3(vec3(0., 0., 0.5)), dir.to_vec3() * 0.5);
+			assert_near(dir.to_mat4().transform_vector3(Vec3::Y), Vec3::Y);
+		}
+	}
+
+	#[test]
+	fn world_positions_round_to_cells() {
+		assert_eq!(world_to_grid(vec3(2.4, 7., -0.6)), (2, -1));
+		for cell in vec![(0, 0), (3, -2), (-5, 8)] {
+			assert_eq!(world_to_grid(cell.to_vec3()), cell);
+			assert_eq!(world_to_grid(cell.to_vec3() + vec3(0.45, 0., -0.45)), cell);
+		}
+	}
+}
@@ -0,0 +1,106 @@
+use super::{Mesh, Texture, Vertex};
+use crate::prelude::*;
+use glam::{vec2, Vec2, Vec3};
+use std::collections::HashMap;
+
+/// Named regions of a single texture, so a bitmap font and HUD icons can share one image
+pub struct TextureAtlas {
+	pub texture: Handle<Texture>,
+	/// Texture size in pixels, used to convert pixel regions to texture coordinates
+	pub size: Vec2,
+	regions: HashMap<String, Rect>,
+}
+
+impl TextureAtlas {
+	pub fn new(texture: Handle<Texture>, width: u32, height: u32) -> Self {
+		Self {
+			texture,
+			size: vec2(width as f32, height as f32),
+			regions: HashMap::new(),
+		}
+	}
+
+	/// Registers a region given in pixels, with the origin at the top left of the texture
+	pub fn add_region(&mut self, name: impl Into<String>, pixels: Rect) -> &mut Self {
+		self.regions.insert(name.into(), pixel_rect_to_uv(pixels, self.size));
+		self
+	}
+
+	/// Texture coordinates of a named region
+	pub fn region(&self, name: &str) -> Option<Rect> {
+		self.regions.get(name).copied()
+	}
+
+	/// Screen-space quad showing a named region, see `screen_quad`
+	pub fn region_quad(&self, name: &str, top_left: Vec2, size: Vec2) -> Option<Mesh> {
+		self.region(name).map(|uv| screen_quad(top_left, size, uv))
+	}
+}
+
+/// Converts a rect in texture pixels to texture coordinates, for a texture of the given size
+pub fn pixel_rect_to_uv(pixels: Rect, texture_size: Vec2) -> Rect {
+	Rect {
+		left: pixels.left / texture_size.x,
+		right: pixels.right / texture_size.x,
+		top: pixels.top / texture_size.y,
+		bottom: pixels.bottom / texture_size.y,
+	}
+}
+
+/// A quad in window pixels showing the `uv` region of a texture, with y pointing down
+pub fn screen_quad(top_left: Vec2, size: Vec2, uv: Rect) -> Mesh {
+	let vertex = |offset: Vec2, uv: Vec2| Vertex {
+		pos: (top_left + offset * size).extend(0.),
+		normal: Vec3::Z,
+		uv,
+		color: Vertex::WHITE,
+	};
+	// counter-clockwise as seen on screen
+	Mesh {
+		vertices: vec![
+			vertex(vec2(0., 0.), vec2(uv.left, uv.top)),
+			vertex(vec2(1., 0.), vec2(uv.right, uv.top)),
+			vertex(vec2(1., 1.), vec2(uv.right, uv.bottom)),
+			vertex(vec2(0., 1.), vec2(uv.left, uv.bottom)),
+		],
+		indices: vec![0, 3, 2, 0, 2, 1],
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn pixel_rect_uvs() {
+		let uv = pixel_rect_to_uv(
+			Rect {
+				left: 4.,
+				right: 7.,
+				top: 0.,
+				bottom: 5.,
+			},
+			vec2(16., 10.),
+		);
+		assert_eq!(
+			(uv.left, uv.right, uv.top, uv.bottom),
+			(0.25, 7. / 16., 0., 0.5)
+		);
+
+		let quad = screen_quad(vec2(10., 20.), vec2(3., 5.), uv);
+		let corners = quad
+			.vertices
+			.iter()
+			.map(|v| (v.pos.truncate(), v.uv))
+			.collect::<Vec<_>>();
+		assert_eq!(
+			corners,
+			vec![
+				(vec2(10., 20.), vec2(0.25, 0.)),
+				(vec2(13., 20.), vec2(7. / 16., 0.)),
+				(vec2(13., 25.), vec2(7. / 16., 0.5)),
+				(vec2(10., 25.), vec2(0.25, 0.5)),
+			]
+		);
+	}
+}
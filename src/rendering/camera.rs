@@ -1,6 +1,7 @@
 use crate::prelude::*;
 use crate::utils::GlobalTransform;
-use glam::{Mat4, Vec3};
+use glam::{vec2, Mat4, Vec3};
+use serde_derive::Deserialize;
 use std::ops::Range;
 
 pub fn plugin(app: &mut AppBuilder) {
@@ -29,16 +30,62 @@ impl Default for CameraBundle {
 
 pub struct Camera {
 	pub field_of_view: f32,
+	/// Window axis the field of view is held along, the other one follows the aspect ratio
+	pub fov_axis: FovAxis,
 	pub clipping_distance: Range<f32>,
+	pub projection: Projection,
+	/// Part of the window drawn to, as fractions of its size from the top left corner. Cameras
+	/// share the depth buffer, so the viewports of several cameras shouldn't overlap.
+	pub viewport: Rect,
 }
 impl Default for Camera {
 	fn default() -> Self {
 		Camera {
 			field_of_view: 60.0,
+			fov_axis: FovAxis::Vertical,
 			clipping_distance: 0.01..100.0,
+			projection: Projection::Perspective,
+			viewport: Rect {
+				left: 0.,
+				right: 1.,
+				top: 0.,
+				bottom: 1.,
+			},
 		}
 	}
 }
+impl Camera {
+	/// The viewport in window pixels as x, y, width and height, with y pointing up like GL does
+	pub fn viewport_pixels(&self, window: &WindowSize) -> (i32, i32, i32, i32) {
+		let x = (self.viewport.left * window.width).round() as i32;
+		let y = ((1. - self.viewport.bottom) * window.height).round() as i32;
+		let width = (self.viewport.right * window.width).round() as i32 - x;
+		let height = ((1. - self.viewport.top) * window.height).round() as i32 - y;
+		(x, y, width, height)
+	}
+
+	fn aspect_ratio(&self, window: &WindowSize) -> f32 {
+		let size = self.viewport.size() * vec2(window.width, window.height);
+		size.x / size.y
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum FovAxis {
+	/// Wider windows show more to the sides
+	Vertical,
+	/// Wider windows show less above and below, keeping extreme aspect ratios from stretching
+	/// the edges of the view
+	Horizontal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Projection {
+	/// Uses the camera field of view
+	Perspective,
+	/// Parallel projection, showing `height` world units vertically
+	Orthographic { height: f32 },
+}
 
 #[derive(Default)]
 pub struct ViewMatrix(pub Mat4);
@@ -58,12 +105,35 @@ fn update_projection_matrix(
 	let changed_cameras: Vec<_> = queries.q1().iter().collect();
 	for (entity, cam, mut projection) in queries.q0_mut().iter_mut() {
 		if changed_cameras.contains(&entity) || window_resized {
-			projection.0 = Mat4::perspective_rh_gl(
-				cam.field_of_view.to_radians(),
-				window.width / window.height,
-				cam.clipping_distance.start,
-				cam.clipping_distance.end,
-			)
+			let aspect = cam.aspect_ratio(&window);
+			projection.0 = match cam.projection {
+				Projection::Perspective => {
+					let vertical_fov = match cam.fov_axis {
+						FovAxis::Vertical => cam.field_of_view.to_radians(),
+						FovAxis::Horizontal => {
+							let half_tan = (cam.field_of_view.to_radians() / 2.).tan();
+							(half_tan / aspect).atan() * 2.
+						}
+					};
+					Mat4::perspective_rh_gl(
+						vertical_fov,
+						aspect,
+						cam.clipping_distance.start,
+						cam.clipping_distance.end,
+					)
+				}
+				Projection::Orthographic { height } => {
+					let (half_width, half_height) = (height * aspect / 2., height / 2.);
+					Mat4::orthographic_rh_gl(
+						-half_width,
+						half_width,
+						-half_height,
+						half_height,
+						cam.clipping_distance.start,
+						cam.clipping_distance.end,
+					)
+				}
+			}
 		}
 	}
 }
@@ -75,3 +145,106 @@ fn update_view_matrix(
 		view.0 = tx.compute_matrix().inverse();
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn recompute_projection_on_camera_change() {
+		let mut app = App::new()
+			.insert_resource(WindowSize {
+				width: 800.,
+				height: 600.,
+			})
+			.add_event::<WindowResize>()
+			.add_system_to_stage(CoreStage::PostUpdate, update_projection_matrix.system())
+			.build();
+		let cam = app.world.spawn().insert_bundle(CameraBundle::default()).id();
+		let projection = |app: &App| app.world.get::<ProjectionMatrix>(cam).unwrap().0;
+
+		app.dispatch_update();
+		let initial = projection(&app);
+		assert_ne!(initial, Mat4::ZERO);
+
+		// an unchanged camera keeps its matrix
+		app.world.get_mut::<ProjectionMatrix>(cam).unwrap().0 = Mat4::ZERO;
+		app.dispatch_update();
+		assert_eq!(projection(&app), Mat4::ZERO);
+
+		app.world.get_mut::<Camera>(cam).unwrap().field_of_view = 90.;
+		app.dispatch_update();
+		assert_ne!(projection(&app), Mat4::ZERO);
+		assert_ne!(projection(&app), initial);
+	}
+
+	#[test]
+	fn hold_field_of_view_along_axis() {
+		let scale_for = |fov_axis: FovAxis, width: f32| {
+			let mut app = App::new()
+				.insert_resource(WindowSize {
+					width,
+					height: 900.,
+				})
+				.add_event::<WindowResize>()
+				.add_system_to_stage(CoreStage::PostUpdate, update_projection_matrix.system())
+				.build();
+			let cam = app
+				.world
+				.spawn()
+				.insert_bundle(CameraBundle {
+					camera: Camera {
+						fov_axis,
+						..Default::default()
+					},
+					..Default::default()
+				})
+				.id();
+			app.dispatch_update();
+			let m = app.world.get::<ProjectionMatrix>(cam).unwrap().0;
+			(m.x_axis.x, m.y_axis.y)
+		};
+		let (ultrawide, standard) = (900. * 21. / 9., 900. * 4. / 3.);
+
+		let (wide_x, wide_y) = scale_for(FovAxis::Vertical, ultrawide);
+		let (std_x, std_y) = scale_for(FovAxis::Vertical, standard);
+		assert!((wide_y - std_y).abs() < 1e-5);
+		assert!(wide_x < std_x);
+
+		let (wide_x, wide_y) = scale_for(FovAxis::Horizontal, ultrawide);
+		let (std_x, std_y) = scale_for(FovAxis::Horizontal, standard);
+		assert!((wide_x - std_x).abs() < 1e-5);
+		assert!(wide_y > std_y);
+		// the horizontal field of view is the configured one
+		assert!((wide_x - 1. / 30f32.to_radians().tan()).abs() < 1e-5);
+	}
+
+	#[test]
+	fn split_screen_viewports() {
+		let window = WindowSize {
+			width: 800.,
+			height: 600.,
+		};
+		let half = |left: f32| Camera {
+			viewport: Rect {
+				left,
+				right: left + 0.5,
+				top: 0.,
+				bottom: 1.,
+			},
+			..Default::default()
+		};
+		assert_eq!(half(0.).viewport_pixels(&window), (0, 0, 400, 600));
+		assert_eq!(half(0.5).viewport_pixels(&window), (400, 0, 400, 600));
+		assert_eq!(half(0.).aspect_ratio(&window), 400. / 600.);
+		// the top quarter, GL counts rows from the bottom
+		let top = Camera {
+			viewport: Rect {
+				bottom: 0.25,
+				..Camera::default().viewport
+			},
+			..Default::default()
+		};
+		assert_eq!(top.viewport_pixels(&window), (0, 450, 800, 150));
+	}
+}
@@ -1,6 +1,10 @@
-use super::{mesh::Mesh, shader::Shader, TextureBindings};
+use super::{
+	mesh::Mesh, shader::Shader, Camera, ProjectionMatrix, RenderSettings, TextureBindings,
+	ViewMatrix,
+};
 use crate::prelude::*;
 use bevy_ecs_wasm::component::Component;
+use glam::{Mat4, Vec3};
 use miniquad::{Bindings, Buffer, Context, PassAction, Pipeline, Texture as ContextTexture};
 use std::collections::HashMap;
 
@@ -9,6 +13,8 @@ pub struct ContextResources {
 	pub textures: HashMap<HandleId, ContextTexture>,
 	pub mesh_buffers: HashMap<HandleId, MeshBufferSet>,
 	pub pipelines: HashMap<HandleId, Pipeline>,
+	/// Same as `pipelines` with face culling disabled, see `RenderSettings::show_backfaces`
+	pub no_cull_pipelines: HashMap<HandleId, Pipeline>,
 }
 
 pub struct MeshBufferSet {
@@ -17,29 +23,104 @@ pub struct MeshBufferSet {
 	pub index_count: usize,
 }
 
-pub fn render<Uniforms: Component>(
+/// Draws only `count` indices of the entity's mesh from `start`, rather than all of them. Parts
+/// of the range past the end of the mesh are left out.
+// nothing draws partial meshes yet
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DrawRange {
+	pub start: u32,
+	pub count: u32,
+}
+
+impl DrawRange {
+	/// First index and number of indices to draw, within a mesh of `index_count` indices
+	fn clamp(&self, index_count: usize) -> (usize, usize) {
+		let start = (self.start as usize).min(index_count);
+		(start, (self.count as usize).min(index_count - start))
+	}
+}
+
+/// The camera a scene is being drawn for
+pub struct CameraView {
+	pub position: Vec3,
+	pub view: Mat4,
+	pub projection: Mat4,
+}
+
+/// Uniforms of scene objects, which take the view of each camera drawing them in turn
+pub trait CameraUniforms: Component + Clone {
+	fn set_camera(&mut self, camera: &CameraView);
+}
+
+type RenderQuery<'a, Uniforms> = Query<
+	'a,
+	(
+		&'static Handle<Mesh>,
+		&'static Handle<Shader>,
+		Option<&'static TextureBindings>,
+		Option<&'static DrawRange>,
+		&'static Uniforms,
+	),
+>;
+
+/// Draws the scene once for each camera, into the camera's viewport
+pub fn render<Uniforms: CameraUniforms>(
 	mut ctx: ResMut<Context>,
 	resources: Res<ContextResources>,
-	query: Query<(
-		&Handle<Mesh>,
-		&Handle<Shader>,
-		Option<&TextureBindings>,
-		&Uniforms,
-	)>,
+	settings: Option<Res<RenderSettings>>,
+	window: Res<WindowSize>,
+	q_cameras: Query<(&Camera, &GlobalTransform, &ViewMatrix, &ProjectionMatrix)>,
+	query: RenderQuery<Uniforms>,
+) {
+	let pipelines = match settings {
+		Some(settings) if settings.show_backfaces => &resources.no_cull_pipelines,
+		_ => &resources.pipelines,
+	};
+	for (camera, transform, view, projection) in q_cameras.iter() {
+		let (x, y, width, height) = camera.viewport_pixels(&window);
+		ctx.apply_viewport(x, y, width, height);
+		let camera_view = CameraView {
+			position: transform.translation,
+			view: view.0,
+			projection: projection.0,
+		};
+		draw_objects(&mut ctx, &resources, pipelines, &query, |ctx, uniforms| {
+			let mut uniforms = uniforms.clone();
+			uniforms.set_camera(&camera_view);
+			ctx.apply_uniforms(&uniforms);
+		});
+	}
+	// overlays cover the whole window
+	ctx.apply_viewport(0, 0, window.width as i32, window.height as i32);
+}
+
+/// Draws screen-space elements over the whole window, after the scene
+pub fn render_overlay<Uniforms: Component>(
+	mut ctx: ResMut<Context>,
+	resources: Res<ContextResources>,
+	query: RenderQuery<Uniforms>,
+) {
+	draw_objects(&mut ctx, &resources, &resources.pipelines, &query, |ctx, uniforms| {
+		ctx.apply_uniforms(uniforms)
+	});
+}
+
+fn draw_objects<Uniforms: Component>(
+	ctx: &mut Context,
+	resources: &ContextResources,
+	pipelines: &HashMap<HandleId, Pipeline>,
+	query: &RenderQuery<Uniforms>,
+	apply_uniforms: impl Fn(&mut Context, &Uniforms),
 ) {
 	let mut grouped_by_shader = query.iter().collect::<Vec<_>>();
 	grouped_by_shader.sort_by(|a, b| a.1.id().cmp(&b.1.id()));
 
-	ctx.begin_default_pass(PassAction::Clear {
-		color: Some((0.2, 0.2, 0.2, 1.0)),
-		depth: Some(1.),
-		stencil: None,
-	});
 	let mut current_shader: Option<HandleId> = None;
-	for (mesh_handle, shader_handle, optional_textures, uniforms) in grouped_by_shader.into_iter() {
+	for (mesh_handle, shader_handle, optional_textures, range, uniforms) in grouped_by_shader {
 		if let (Some(mesh), Some(pipeline)) = (
 			resources.mesh_buffers.get(&mesh_handle.id()),
-			resources.pipelines.get(&shader_handle.id()),
+			pipelines.get(&shader_handle.id()),
 		) {
 			let images = if let Some(TextureBindings(bindings)) = optional_textures {
 				let resolved = bindings
@@ -65,11 +146,43 @@ pub fn render<Uniforms: Component>(
 				index_buffer: mesh.index,
 				images,
 			});
-			ctx.apply_uniforms(uniforms);
-			ctx.draw(0, mesh.index_count as i32, 1);
+			apply_uniforms(ctx, uniforms);
+			let (start, count) = match range {
+				Some(range) => range.clamp(mesh.index_count),
+				None => (0, mesh.index_count),
+			};
+			ctx.draw(start as i32, count as i32, 1);
 		}
 	}
+}
+
+pub fn begin_frame(mut ctx: ResMut<Context>, settings: Option<Res<RenderSettings>>) {
+	let [r, g, b, a] = settings
+		.map(|s| s.clear_color)
+		.unwrap_or_else(|| RenderSettings::default().clear_color)
+		.as_linear_rgba_f32();
+	ctx.begin_default_pass(PassAction::Clear {
+		color: Some((r, g, b, a)),
+		depth: Some(1.),
+		stencil: None,
+	});
+}
 
+pub fn end_frame(mut ctx: ResMut<Context>) {
 	ctx.end_render_pass();
 	ctx.commit_frame();
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn draw_range_stays_within_mesh() {
+		let range = |start, count| DrawRange { start, count };
+		assert_eq!(range(6, 12).clamp(36), (6, 12));
+		assert_eq!(range(30, 12).clamp(36), (30, 6));
+		assert_eq!(range(40, 12).clamp(36), (36, 0));
+		assert_eq!(range(0, 0).clamp(36), (0, 0));
+	}
+}
@@ -2,8 +2,9 @@ use crate::assets::{AssetEvent, Assets};
 
 use super::draw::{ContextResources, MeshBufferSet};
 use crate::prelude::*;
-use glam::{Mat4, Vec2, Vec3};
+use glam::{IVec3, Mat4, Vec2, Vec3};
 use miniquad::{Buffer, BufferType, Context, VertexAttribute, VertexFormat};
+use std::collections::{HashMap, HashSet};
 
 #[derive(Clone)]
 #[repr(C)]
@@ -11,14 +12,20 @@ pub struct Vertex {
 	pub pos: Vec3,
 	pub normal: Vec3,
 	pub uv: Vec2,
+	/// Linear RGBA, multiplied into the shaded color
+	pub color: [f32; 4],
 }
 
 impl Vertex {
+	/// Vertex color that leaves the shaded color unchanged
+	pub const WHITE: [f32; 4] = [1.; 4];
+
 	pub fn attributes<'a>() -> Vec<VertexAttribute> {
 		vec![
 			VertexAttribute::new("pos", VertexFormat::Float3),
 			VertexAttribute::new("normal", VertexFormat::Float3),
 			VertexAttribute::new("uv", VertexFormat::Float2),
+			VertexAttribute::new("color", VertexFormat::Float4),
 		]
 	}
 
@@ -27,8 +34,17 @@ impl Vertex {
 			pos: mat.transform_point3(self.pos),
 			normal: mat.transform_vector3(self.normal),
 			uv: self.uv,
+			color: self.color,
 		}
 	}
+
+	/// Whether every attribute of `other` is within `epsilon` of this vertex's
+	fn is_near(&self, other: &Vertex, epsilon: f32) -> bool {
+		self.pos.abs_diff_eq(other.pos, epsilon)
+			&& self.normal.abs_diff_eq(other.normal, epsilon)
+			&& self.uv.abs_diff_eq(other.uv, epsilon)
+			&& self.color.iter().zip(other.color.iter()).all(|(a, b)| (a - b).abs() <= epsilon)
+	}
 }
 
 #[derive(Clone)]
@@ -58,6 +74,43 @@ impl Mesh {
 			indices: self.indices.clone(),
 		}
 	}
+
+	/// Merges vertices whose position, normal, uv and color are all within `epsilon` of each
+	/// other, and points the indices at the vertices kept. Vertices on either side of a hard edge
+	/// or a texture seam differ in normal or uv, and stay apart.
+	pub fn weld(&mut self, epsilon: f32) {
+		let cell_size = epsilon.max(f32::EPSILON);
+		let cell_of = |pos: Vec3| (pos / cell_size).floor().as_i32();
+		// kept vertices by the grid cell of their position, near ones are in neighboring cells
+		let mut cells: HashMap<IVec3, Vec<u16>> = HashMap::new();
+		let mut welded: Vec<Vertex> = vec![];
+		let mut new_indices = Vec::with_capacity(self.vertices.len());
+		for vertex in self.vertices.drain(..) {
+			let cell = cell_of(vertex.pos);
+			let neighbor_cells = (-1..=1).flat_map(|x| {
+				(-1..=1).flat_map(move |y| (-1..=1).map(move |z| cell + IVec3::new(x, y, z)))
+			});
+			let existing = neighbor_cells
+				.filter_map(|c| cells.get(&c))
+				.flatten()
+				.copied()
+				.find(|&i| welded[i as usize].is_near(&vertex, epsilon));
+			let index = match existing {
+				Some(index) => index,
+				None => {
+					let index = welded.len() as u16;
+					welded.push(vertex);
+					cells.entry(cell).or_default().push(index);
+					index
+				}
+			};
+			new_indices.push(index);
+		}
+		for index in self.indices.iter_mut() {
+			*index = new_indices[*index as usize];
+		}
+		self.vertices = welded;
+	}
 }
 
 pub fn upload_meshes(
@@ -66,12 +119,14 @@ pub fn upload_meshes(
 	mut context: ResMut<Context>,
 	mut context_resources: ResMut<ContextResources>,
 ) {
+	let mut uploaded = HashSet::new();
 	for evt in mesh_events.iter() {
-		if let AssetEvent::Added(handle) = evt {
-			if let Some(mesh) = meshes.get(handle) {
-				let overwritten = context_resources
-					.mesh_buffers
-					.insert(
+		match evt {
+			AssetEvent::Added(handle) => {
+				let first_upload = uploaded.insert(handle.id());
+				debug_assert!(first_upload, "uploading duplicate mesh in one frame");
+				if let Some(mesh) = meshes.get(handle) {
+					let replaced = context_resources.mesh_buffers.insert(
 						handle.id(),
 						MeshBufferSet {
 							vertex: Buffer::immutable(
@@ -86,12 +141,56 @@ pub fn upload_meshes(
 							),
 							index_count: mesh.indices.len(),
 						},
-					)
-					.is_some();
-				if overwritten {
-					panic!("uploading duplicate mesh");
+					);
+					// reloaded asset
+					if let Some(buffers) = replaced {
+						buffers.vertex.delete();
+						buffers.index.delete();
+					}
+				}
+			}
+			AssetEvent::Removed(handle) => {
+				if let Some(buffers) = context_resources.mesh_buffers.remove(&handle.id()) {
+					buffers.vertex.delete();
+					buffers.index.delete();
 				}
 			}
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use glam::{vec2, vec3};
+
+	/// Quad on the XY plane spanning `x` to `x + 1`, with uvs continuing across neighbors
+	fn quad(x: f32, normal: Vec3) -> Mesh {
+		let vertex = |dx: f32, y: f32| Vertex {
+			pos: vec3(x + dx, y, 0.),
+			normal,
+			uv: vec2(x + dx, y),
+			color: Vertex::WHITE,
+		};
+		Mesh {
+			vertices: vec![vertex(0., 0.), vertex(1., 0.), vertex(1., 1.), vertex(0., 1.)],
+			indices: vec![0, 1, 2, 0, 2, 3],
+		}
+	}
+
+	#[test]
+	fn weld_shared_edge_of_coplanar_quads() {
+		let mut mesh = quad(0., Vec3::Z);
+		// off by less than epsilon, as after transforming the quads separately
+		mesh.extend_with(quad(1. + 1e-6, Vec3::Z));
+		mesh.weld(1e-4);
+		assert_eq!(mesh.vertices.len(), 6);
+		assert_eq!(mesh.indices, vec![0, 1, 2, 0, 2, 3, 1, 4, 5, 1, 5, 2]);
+
+		// a differently facing quad along the same edge keeps its own vertices
+		mesh.extend_with(quad(1., Vec3::X));
+		mesh.weld(1e-4);
+		assert_eq!(mesh.vertices.len(), 10);
+		assert_eq!(mesh.indices.len(), 18);
+	}
+}
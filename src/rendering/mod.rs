@@ -1,19 +1,28 @@
+mod atlas;
 mod camera;
 mod draw;
 mod mesh;
+mod overlay;
 mod shader;
+mod text;
 mod texture;
 
 use crate::prelude::*;
+pub use atlas::{pixel_rect_to_uv, screen_quad, TextureAtlas};
 use bevy_ecs_wasm::component::Component;
-pub use camera::{Camera, CameraBundle, ProjectionMatrix, ViewMatrix};
+pub use camera::{Camera, CameraBundle, FovAxis, Projection, ProjectionMatrix, ViewMatrix};
+pub use draw::{CameraUniforms, CameraView, DrawRange};
 pub use mesh::{Mesh, Vertex};
+pub use overlay::{OverlayUniforms, ScreenQuad};
 use miniquad::PipelineParams;
 pub use shader::{Shader, ShaderMetaStore};
+pub use text::Text;
 pub use texture::{Texture, TextureBindings, TextureLoadSettings, TextureProperties};
 
 pub fn plugin(app: &mut AppBuilder) {
-	app.add_asset_type::<Texture>()
+	// the Context and WindowSize resources are provided by the backend runner
+	app.require_event::<WindowResize>("rendering::plugin")
+		.add_asset_type::<Texture>()
 		.add_asset_type::<Mesh>()
 		.add_asset_type::<Shader>()
 		.insert_resource(draw::ContextResources::default())
@@ -21,19 +30,43 @@ pub fn plugin(app: &mut AppBuilder) {
 		.insert_resource(shader::ShaderMetaStore::default())
 		.use_asset_processor(texture::process_png_texture)
 		.use_asset_processor(shader::process_shader_source)
+		.use_asset_includes::<Shader>("#include")
 		.add_system_to_stage(CoreStage::AssetEvents, texture::upload_textures.system())
 		.add_system_to_stage(CoreStage::AssetEvents, mesh::upload_meshes.system())
 		.add_system_to_stage(CoreStage::AssetEvents, shader::upload_shaders.system())
-		.add_plugin(camera::plugin);
+		.add_system_to_stage(CoreStage::PostUpdate, propagate_transforms.system())
+		.add_system_to_stage(CoreStage::Render, draw::begin_frame.system())
+		.add_system_to_stage(CoreStage::Last, draw::end_frame.system())
+		.add_plugin(camera::plugin)
+		.add_plugin(overlay::plugin)
+		.add_plugin(text::plugin);
 }
 
-#[derive(Default)]
 pub struct RenderSettings {
 	pub pipeline: PipelineParams,
+	pub clear_color: Color,
+	/// Draw faces pointing away from the camera too, to tell meshes with reversed winding from
+	/// missing ones. Can be changed at runtime.
+	pub show_backfaces: bool,
+}
+
+impl Default for RenderSettings {
+	fn default() -> Self {
+		Self {
+			pipeline: Default::default(),
+			clear_color: Color::rgb_linear(0.2, 0.2, 0.2),
+			show_backfaces: false,
+		}
+	}
 }
 
 impl AppBuilder {
-	pub fn register_shader_uniforms<T: Component>(&mut self) -> &mut Self {
+	pub fn register_shader_uniforms<T: CameraUniforms>(&mut self) -> &mut Self {
 		self.add_system_to_stage(CoreStage::Render, draw::render::<T>.system())
 	}
+
+	/// Like `register_shader_uniforms`, but drawn after the scene for screen-space elements
+	pub fn register_overlay_uniforms<T: Component>(&mut self) -> &mut Self {
+		self.add_system_to_stage(CoreStage::RenderOverlay, draw::render_overlay::<T>.system())
+	}
 }
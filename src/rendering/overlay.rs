@@ -0,0 +1,153 @@
+use super::{
+	screen_quad, Mesh, Shader, ShaderMetaStore, Texture, TextureBindings, TextureLoadSettings,
+	TextureProperties,
+};
+use crate::prelude::*;
+use glam::{vec2, Mat4, Vec4};
+use miniquad::{
+	BlendFactor, BlendState, BlendValue, Equation, FilterMode, PipelineParams, TextureFormat,
+	TextureWrap, UniformType,
+};
+
+pub fn plugin(app: &mut AppBuilder) {
+	app.register_overlay_uniforms::<OverlayUniforms>()
+		.add_startup_system(load_overlay_assets.system())
+		.add_system_to_stage(CoreStage::PostUpdate, update_screen_quads.system())
+		.add_system_to_stage(CoreStage::PostUpdate, update_overlay_projection.system());
+}
+
+/// A rectangle drawn in window pixels on top of the scene, regardless of the 3D camera.
+/// A fixed HUD element is spawned with just the component:
+/// ```ignore
+/// cmd.spawn().insert(ScreenQuad {
+/// 	color: Color::rgba(0., 0., 0., 0.5),
+/// 	..ScreenQuad::new(Rect::from_center(vec2(100., 100.), vec2(64., 64.)))
+/// });
+/// ```
+pub struct ScreenQuad {
+	/// Covered area in window pixels, with the origin at the top left corner
+	pub rect: Rect,
+	/// Drawn as a solid color if not set
+	pub texture: Option<Handle<Texture>>,
+	/// Region of the texture to show, see `TextureAtlas` for named regions
+	pub uv: Rect,
+	/// Multiplies the texture color, the alpha channel blends with the scene
+	pub color: Color,
+}
+
+impl ScreenQuad {
+	pub fn new(rect: Rect) -> Self {
+		Self {
+			rect,
+			texture: None,
+			uv: Rect {
+				left: 0.,
+				right: 1.,
+				top: 0.,
+				bottom: 1.,
+			},
+			color: Color::WHITE,
+		}
+	}
+}
+
+#[repr(C)]
+pub struct OverlayUniforms {
+	projection: Mat4,
+	color: Vec4,
+}
+
+struct OverlayAssets {
+	shader: Handle<Shader>,
+	blank: Handle<Texture>,
+}
+
+fn load_overlay_assets(
+	mut cmd: Commands,
+	mut shaders: ResMut<Assets<Shader>>,
+	mut shader_meta: ResMut<ShaderMetaStore>,
+	mut textures: ResMut<Assets<Texture>>,
+	mut texture_settings: ResMut<TextureLoadSettings>,
+) {
+	let shader = shaders.load("assets/overlay.glsl");
+	shader_meta.set(
+		&shader,
+		&["tex"],
+		&[
+			("projection", UniformType::Mat4),
+			("color", UniformType::Float4),
+		],
+	);
+	// drawn in order on top of the scene, without touching the depth buffer
+	shader_meta.set_pipeline(
+		&shader,
+		PipelineParams {
+			color_blend: Some(BlendState::new(
+				Equation::Add,
+				BlendFactor::Value(BlendValue::SourceAlpha),
+				BlendFactor::OneMinusValue(BlendValue::SourceAlpha),
+			)),
+			..Default::default()
+		},
+	);
+
+	let blank = textures.add(Texture {
+		data: vec![255; 4],
+		width: 1,
+		height: 1,
+		format: TextureFormat::RGBA8,
+	});
+	texture_settings.set(
+		&blank,
+		TextureProperties {
+			wrap: TextureWrap::Clamp,
+			filter: FilterMode::Nearest,
+			anisotropy: 0.0,
+			generate_mipmaps: false,
+		},
+	);
+
+	cmd.insert_resource(OverlayAssets { shader, blank });
+}
+
+fn update_screen_quads(
+	mut cmd: Commands,
+	mut meshes: ResMut<Assets<Mesh>>,
+	overlay_assets: Res<OverlayAssets>,
+	window: Res<WindowSize>,
+	q: Query<(Entity, &ScreenQuad), Changed<ScreenQuad>>,
+) {
+	for (entity, quad) in q.iter() {
+		let top_left = vec2(quad.rect.left, quad.rect.top);
+		let texture = quad
+			.texture
+			.clone()
+			.unwrap_or_else(|| overlay_assets.blank.clone());
+		cmd.entity(entity).insert_bundle((
+			meshes.add(screen_quad(top_left, quad.rect.size(), quad.uv)),
+			overlay_assets.shader.clone(),
+			TextureBindings(vec![texture]),
+			OverlayUniforms {
+				projection: screen_projection(&window),
+				color: quad.color.into(),
+			},
+		));
+	}
+}
+
+fn update_overlay_projection(
+	mut q: Query<&mut OverlayUniforms>,
+	mut resize_event: EventReader<WindowResize>,
+	window: Res<WindowSize>,
+) {
+	if resize_event.iter().count() > 0 {
+		for mut uniforms in q.iter_mut() {
+			uniforms.projection = screen_projection(&window);
+		}
+	}
+}
+
+/// Maps window pixels to clip space, with the origin at the top left corner
+pub(super) fn screen_projection(window: &WindowSize) -> Mat4 {
+	Mat4::orthographic_rh_gl(0., window.width, window.height, 0., -1., 1.)
+}
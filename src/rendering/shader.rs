@@ -1,9 +1,12 @@
-use std::{collections::HashMap, str};
+use std::{
+	collections::{HashMap, HashSet},
+	str,
+};
 
 use super::{draw::ContextResources, mesh::Vertex, RenderSettings};
 use crate::prelude::*;
 use miniquad::{
-	BufferLayout, Context, Pipeline, PipelineParams, Shader as ContextShader, ShaderMeta,
+	BufferLayout, Context, CullFace, Pipeline, PipelineParams, Shader as ContextShader, ShaderMeta,
 	UniformBlockLayout, UniformDesc, UniformType,
 };
 
@@ -22,7 +25,10 @@ impl Shader {
 }
 
 #[derive(Default)]
-pub struct ShaderMetaStore(HashMap<HandleId, ShaderMetadata>);
+pub struct ShaderMetaStore {
+	metadata: HashMap<HandleId, ShaderMetadata>,
+	pipelines: HashMap<HandleId, PipelineParams>,
+}
 impl ShaderMetaStore {
 	pub fn set(
 		&mut self,
@@ -30,7 +36,7 @@ impl ShaderMetaStore {
 		textures: &[&str],
 		uniforms: &[(&str, UniformType)],
 	) {
-		self.0.insert(
+		self.metadata.insert(
 			for_shader.id(),
 			ShaderMetadata {
 				textures: textures.into_iter().map(|s| s.to_string()).collect(),
@@ -41,6 +47,11 @@ impl ShaderMetaStore {
 			},
 		);
 	}
+
+	/// Overrides the `RenderSettings` pipeline for a single shader
+	pub fn set_pipeline(&mut self, for_shader: &Handle<Shader>, params: PipelineParams) {
+		self.pipelines.insert(for_shader.id(), params);
+	}
 }
 
 struct ShaderMetadata {
@@ -78,44 +89,54 @@ pub fn upload_shaders(
 			&shader.vertex,
 			&shader.fragment,
 			meta_store
-				.0
+				.metadata
 				.get(&handle.id())
 				.unwrap_or_else(|| panic!("shader requires metadata: {:?}", handle.id()))
 				.into(),
 		);
-		let pipeline_params = match settings {
-			Some(ref res) => PipelineParams { ..res.pipeline },
-			None => Default::default(),
+		let pipeline_params = match (meta_store.pipelines.get(&handle.id()), &settings) {
+			(Some(params), _) => *params,
+			(None, Some(res)) => PipelineParams { ..res.pipeline },
+			(None, None) => Default::default(),
 		};
 		match shader {
 			Ok(shader) => {
-				let overwritten = ctx
-					.pipelines
-					.insert(
-						handle.id(),
-						Pipeline::with_params(
-							&mut context,
-							&[BufferLayout::default()],
-							&Vertex::attributes(),
-							shader,
-							pipeline_params,
-						),
+				let mut new_pipeline = |params| {
+					Pipeline::with_params(
+						&mut context,
+						&[BufferLayout::default()],
+						&Vertex::attributes(),
+						shader,
+						params,
 					)
-					.is_some();
-				if overwritten {
-					panic!("uploading duplicate shader");
-				}
+				};
+				// a reloaded shader replaces the pipelines, miniquad offers no way to delete the
+				// old ones
+				ctx.pipelines.insert(handle.id(), new_pipeline(pipeline_params));
+				ctx.no_cull_pipelines.insert(
+					handle.id(),
+					new_pipeline(PipelineParams {
+						cull_face: CullFace::Nothing,
+						..pipeline_params
+					}),
+				);
 			}
 			Err(e) => error!("Shader compilation error: {}", e),
 		}
 	};
 	fn discard_shader(handle: &Handle<Shader>, ctx: &mut ContextResources) {
 		ctx.pipelines.remove(&handle.id());
+		ctx.no_cull_pipelines.remove(&handle.id());
 	}
 
+	let mut uploaded = HashSet::new();
 	for evt in shader_events.iter() {
 		match evt {
-			AssetEvent::Added(handle) => register_shader(handle, &mut context_resources),
+			AssetEvent::Added(handle) => {
+				let first_upload = uploaded.insert(handle.id());
+				debug_assert!(first_upload, "uploading duplicate shader in one frame");
+				register_shader(handle, &mut context_resources)
+			}
 			AssetEvent::Removed(handle) => discard_shader(handle, &mut context_resources),
 		}
 	}
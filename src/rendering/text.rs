@@ -0,0 +1,221 @@
+use super::{
+	overlay::screen_projection, screen_quad, Mesh, Shader, ShaderMetaStore, Texture,
+	TextureBindings, TextureLoadSettings, TextureProperties,
+};
+use crate::prelude::*;
+use glam::{vec2, Mat4, Vec2, Vec3};
+use miniquad::{FilterMode, TextureFormat, TextureWrap, UniformType};
+
+pub fn plugin(app: &mut AppBuilder) {
+	// drawn after the screen quads of the overlay plugin, so text can sit on top of them
+	app.register_overlay_uniforms::<TextUniforms>()
+		.add_startup_system(load_text_assets.system())
+		.add_system_to_stage(CoreStage::PostUpdate, update_text_meshes.system())
+		.add_system_to_stage(CoreStage::PostUpdate, update_text_projection.system());
+}
+
+/// A line (or several, separated by '\n') of screen-space text drawn with the built-in bitmap font
+pub struct Text {
+	pub value: String,
+	/// Top left corner in window pixels
+	pub position: Vec2,
+	/// Size of a single font pixel in window pixels
+	pub scale: f32,
+	pub color: Color,
+}
+
+#[repr(C)]
+pub struct TextUniforms {
+	projection: Mat4,
+	color: Vec3,
+}
+
+struct TextAssets {
+	shader: Handle<Shader>,
+	font: Handle<Texture>,
+}
+
+fn load_text_assets(
+	mut cmd: Commands,
+	mut shaders: ResMut<Assets<Shader>>,
+	mut shader_meta: ResMut<ShaderMetaStore>,
+	mut textures: ResMut<Assets<Texture>>,
+	mut texture_settings: ResMut<TextureLoadSettings>,
+) {
+	let shader = shaders.load("assets/text.glsl");
+	shader_meta.set(
+		&shader,
+		&["font_tex"],
+		&[
+			("projection", UniformType::Mat4),
+			("color", UniformType::Float3),
+		],
+	);
+
+	let font = textures.add(build_font_texture());
+	texture_settings.set(
+		&font,
+		TextureProperties {
+			wrap: TextureWrap::Clamp,
+			filter: FilterMode::Nearest,
+			anisotropy: 0.0,
+			generate_mipmaps: false,
+		},
+	);
+
+	cmd.insert_resource(TextAssets { shader, font });
+}
+
+fn update_text_meshes(
+	mut cmd: Commands,
+	mut meshes: ResMut<Assets<Mesh>>,
+	text_assets: Res<TextAssets>,
+	window: Res<WindowSize>,
+	q: Query<(Entity, &Text), Changed<Text>>,
+) {
+	for (entity, text) in q.iter() {
+		cmd.entity(entity).insert_bundle((
+			meshes.add(build_text_mesh(text)),
+			text_assets.shader.clone(),
+			TextureBindings(vec![text_assets.font.clone()]),
+			TextUniforms {
+				projection: screen_projection(&window),
+				color: text.color.into(),
+			},
+		));
+	}
+}
+
+fn update_text_projection(
+	mut q: Query<&mut TextUniforms>,
+	mut resize_event: EventReader<WindowResize>,
+	window: Res<WindowSize>,
+) {
+	if resize_event.iter().count() > 0 {
+		for mut uniforms in q.iter_mut() {
+			uniforms.projection = screen_projection(&window);
+		}
+	}
+}
+
+const GLYPH_WIDTH: usize = 3;
+const GLYPH_HEIGHT: usize = 5;
+// glyphs are padded by one empty pixel to prevent bleeding between neighbors
+const GLYPH_STRIDE: usize = GLYPH_WIDTH + 1;
+const ATLAS_HEIGHT: usize = GLYPH_HEIGHT + 1;
+const LINE_SPACING: usize = 2;
+
+fn build_text_mesh(text: &Text) -> Mesh {
+	let mut mesh = Mesh::new();
+	let glyph_size = vec2(GLYPH_WIDTH as f32, GLYPH_HEIGHT as f32) * text.scale;
+	let mut cursor = text.position;
+	for c in text.value.chars() {
+		if c == '\n' {
+			cursor.x = text.position.x;
+			cursor.y += glyph_size.y + LINE_SPACING as f32 * text.scale;
+			continue;
+		}
+		let (u_min, u_max) = glyph_uv_range(c);
+		let uv = Rect {
+			left: u_min,
+			right: u_max,
+			top: 0.,
+			bottom: GLYPH_HEIGHT as f32 / ATLAS_HEIGHT as f32,
+		};
+		mesh.extend_with(screen_quad(cursor, glyph_size, uv));
+		cursor.x += glyph_size.x + text.scale;
+	}
+	mesh
+}
+
+fn glyph_uv_range(c: char) -> (f32, f32) {
+	let c = c.to_ascii_uppercase();
+	let index = GLYPHS
+		.iter()
+		.position(|(g, _)| *g == c)
+		.or_else(|| GLYPHS.iter().position(|(g, _)| *g == '?'))
+		.unwrap();
+	let atlas_width = (GLYPHS.len() * GLYPH_STRIDE) as f32;
+	let left = (index * GLYPH_STRIDE) as f32;
+	(left / atlas_width, (left + GLYPH_WIDTH as f32) / atlas_width)
+}
+
+fn build_font_texture() -> Texture {
+	let width = GLYPHS.len() * GLYPH_STRIDE;
+	let mut data = vec![0u8; width * ATLAS_HEIGHT * 4];
+	for (i, (_, rows)) in GLYPHS.iter().enumerate() {
+		for (y, row) in rows.iter().enumerate() {
+			for x in 0..GLYPH_WIDTH {
+				if row & (1 << (GLYPH_WIDTH - 1 - x)) != 0 {
+					let offset = (y * width + i * GLYPH_STRIDE + x) * 4;
+					data[offset..offset + 4].copy_from_slice(&[255; 4]);
+				}
+			}
+		}
+	}
+	Texture {
+		data,
+		width: width as u32,
+		height: ATLAS_HEIGHT as u32,
+		format: TextureFormat::RGBA8,
+	}
+}
+
+// 3x5 pixel glyphs, one row per byte, most significant of the three bits on the left
+#[rustfmt::skip]
+const GLYPHS: &[(char, [u8; GLYPH_HEIGHT])] = &[
+	(' ', [0b000, 0b000, 0b000, 0b000, 0b000]),
+	('0', [0b111, 0b101, 0b101, 0b101, 0b111]),
+	('1', [0b010, 0b110, 0b010, 0b010, 0b111]),
+	('2', [0b111, 0b001, 0b111, 0b100, 0b111]),
+	('3', [0b111, 0b001, 0b111, 0b001, 0b111]),
+	('4', [0b101, 0b101, 0b111, 0b001, 0b001]),
+	('5', [0b111, 0b100, 0b111, 0b001, 0b111]),
+	('6', [0b111, 0b100, 0b111, 0b101, 0b111]),
+	('7', [0b111, 0b001, 0b001, 0b010, 0b010]),
+	('8', [0b111, 0b101, 0b111, 0b101, 0b111]),
+	('9', [0b111, 0b101, 0b111, 0b001, 0b111]),
+	('A', [0b010, 0b101, 0b111, 0b101, 0b101]),
+	('B', [0b110, 0b101, 0b110, 0b101, 0b110]),
+	('C', [0b011, 0b100, 0b100, 0b100, 0b011]),
+	('D', [0b110, 0b101, 0b101, 0b101, 0b110]),
+	('E', [0b111, 0b100, 0b110, 0b100, 0b111]),
+	('F', [0b111, 0b100, 0b110, 0b100, 0b100]),
+	('G', [0b011, 0b100, 0b101, 0b101, 0b011]),
+	('H', [0b101, 0b101, 0b111, 0b101, 0b101]),
+	('I', [0b111, 0b010, 0b010, 0b010, 0b111]),
+	('J', [0b001, 0b001, 0b001, 0b101, 0b010]),
+	('K', [0b101, 0b101, 0b110, 0b101, 0b101]),
+	('L', [0b100, 0b100, 0b100, 0b100, 0b111]),
+	('M', [0b101, 0b111, 0b111, 0b101, 0b101]),
+	('N', [0b110, 0b101, 0b101, 0b101, 0b101]),
+	('O', [0b010, 0b101, 0b101, 0b101, 0b010]),
+	('P', [0b110, 0b101, 0b110, 0b100, 0b100]),
+	('Q', [0b010, 0b101, 0b101, 0b110, 0b011]),
+	('R', [0b110, 0b101, 0b110, 0b101, 0b101]),
+	('S', [0b011, 0b100, 0b010, 0b001, 0b110]),
+	('T', [0b111, 0b010, 0b010, 0b010, 0b010]),
+	('U', [0b101, 0b101, 0b101, 0b101, 0b111]),
+	('V', [0b101, 0b101, 0b101, 0b101, 0b010]),
+	('W', [0b101, 0b101, 0b111, 0b111, 0b101]),
+	('X', [0b101, 0b101, 0b010, 0b101, 0b101]),
+	('Y', [0b101, 0b101, 0b010, 0b010, 0b010]),
+	('Z', [0b111, 0b001, 0b010, 0b100, 0b111]),
+	('.', [0b000, 0b000, 0b000, 0b000, 0b010]),
+	(',', [0b000, 0b000, 0b000, 0b010, 0b100]),
+	(':', [0b000, 0b010, 0b000, 0b010, 0b000]),
+	('-', [0b000, 0b000, 0b111, 0b000, 0b000]),
+	('+', [0b000, 0b010, 0b111, 0b010, 0b000]),
+	('=', [0b000, 0b111, 0b000, 0b111, 0b000]),
+	('_', [0b000, 0b000, 0b000, 0b000, 0b111]),
+	('/', [0b001, 0b001, 0b010, 0b100, 0b100]),
+	('%', [0b101, 0b001, 0b010, 0b100, 0b101]),
+	('#', [0b101, 0b111, 0b101, 0b111, 0b101]),
+	('!', [0b010, 0b010, 0b010, 0b000, 0b010]),
+	('?', [0b110, 0b001, 0b010, 0b000, 0b010]),
+	('(', [0b001, 0b010, 0b010, 0b010, 0b001]),
+	(')', [0b100, 0b010, 0b010, 0b010, 0b100]),
+	('<', [0b001, 0b010, 0b100, 0b010, 0b001]),
+	('>', [0b100, 0b010, 0b001, 0b010, 0b100]),
+	('\'', [0b010, 0b010, 0b000, 0b000, 0b000]),
+];
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use super::draw::ContextResources;
 use crate::prelude::*;
@@ -26,6 +26,14 @@ impl TextureLoadSettings {
 	pub fn set_defaults(&mut self, props: TextureProperties) {
 		self.defaults = props;
 	}
+
+	pub fn set(&mut self, for_texture: &Handle<Texture>, props: TextureProperties) {
+		self.per_asset.insert(for_texture.id(), props);
+	}
+
+	pub fn get(&self, for_texture: &Handle<Texture>) -> TextureProperties {
+		*self.per_asset.get(&for_texture.id()).unwrap_or(&self.defaults)
+	}
 }
 
 #[derive(Clone, Copy)]
@@ -33,6 +41,8 @@ pub struct TextureProperties {
 	pub wrap: TextureWrap,
 	pub filter: FilterMode,
 	pub anisotropy: f32,
+	/// Generate the mip chain on upload and sample it with trilinear filtering
+	pub generate_mipmaps: bool,
 }
 impl Default for TextureProperties {
 	fn default() -> Self {
@@ -40,6 +50,7 @@ impl Default for TextureProperties {
 			wrap: TextureWrap::Clamp,
 			filter: FilterMode::Linear,
 			anisotropy: 0.0,
+			generate_mipmaps: false,
 		}
 	}
 }
@@ -50,38 +61,44 @@ pub fn upload_textures(
 	mut context: ResMut<Context>,
 	mut context_resources: ResMut<ContextResources>,
 	load_settings: Res<TextureLoadSettings>,
+	capabilities: Res<GraphicsCapabilities>,
 ) {
+	let mut uploaded = HashSet::new();
 	for evt in texture_events.iter() {
+		if let AssetEvent::Removed(handle) = evt {
+			if let Some(texture) = context_resources.textures.remove(&handle.id()) {
+				texture.delete();
+			}
+		}
 		if let AssetEvent::Added(handle) = evt {
+			let first_upload = uploaded.insert(handle.id());
+			debug_assert!(first_upload, "uploading duplicate texture in one frame");
 			if let Some(tex) = textures.get(handle) {
 				let TextureProperties {
 					wrap,
 					filter,
 					anisotropy,
-				} = load_settings
-					.per_asset
-					.get(&handle.id())
-					.unwrap_or_else(|| &load_settings.defaults);
-				let overwritten = context_resources
-					.textures
-					.insert(
-						handle.id(),
-						ContextTexture::from_data_and_format(
-							&mut context,
-							&tex.data,
-							TextureParams {
-								format: tex.format,
-								width: tex.width,
-								height: tex.height,
-								wrap: *wrap,
-								filter: *filter,
-								anisotropy: *anisotropy,
-							},
-						),
-					)
-					.is_some();
-				if overwritten {
-					panic!("uploading duplicate texture");
+					generate_mipmaps,
+				} = load_settings.get(handle);
+				let texture = ContextTexture::from_data_and_format(
+					&mut context,
+					&tex.data,
+					TextureParams {
+						format: tex.format,
+						width: tex.width,
+						height: tex.height,
+						wrap,
+						filter,
+						anisotropy: capabilities.effective_anisotropy(anisotropy),
+					},
+				);
+				if generate_mipmaps {
+					texture.generate_mipmaps(&mut context);
+				}
+				let replaced = context_resources.textures.insert(handle.id(), texture);
+				// reloaded asset
+				if let Some(texture) = replaced {
+					texture.delete();
 				}
 			}
 		}
@@ -1,6 +1,7 @@
-use glam::Vec2;
+use glam::{vec2, Vec2};
 
-#[derive(Clone, Copy)]
+/// Axis aligned rectangle, with `top` less than `bottom` like in screen space
+#[derive(Clone, Copy, PartialEq)]
 pub struct Rect {
 	pub left: f32,
 	pub right: f32,
@@ -9,11 +10,36 @@ pub struct Rect {
 }
 
 impl Rect {
+	/// Creates a rect of the given size, with `top` above (less than) `bottom`
+	pub fn from_center(center: Vec2, size: Vec2) -> Self {
+		let extent = size / 2.;
+		Self {
+			left: center.x - extent.x,
+			right: center.x + extent.x,
+			top: center.y - extent.y,
+			bottom: center.y + extent.y,
+		}
+	}
+
+	pub fn center(self) -> Vec2 {
+		vec2((self.left + self.right) / 2., (self.top + self.bottom) / 2.)
+	}
+
+	/// Width and height, assuming `top` is less than `bottom` like in `contains`
+	pub fn size(self) -> Vec2 {
+		vec2(self.right - self.left, self.bottom - self.top)
+	}
+
+	pub fn area(self) -> f32 {
+		let size = self.size();
+		size.x * size.y
+	}
+
 	pub fn intersects(self, other: Self) -> bool {
 		!(other.right < self.left
 			|| self.right < other.left
-			|| other.top < self.bottom
-			|| self.top < other.bottom)
+			|| other.bottom < self.top
+			|| self.bottom < other.top)
 	}
 
 	pub fn contains(self, v: Vec2) -> bool {
@@ -41,4 +67,39 @@ mod tests {
 		assert_eq!(r.contains(vec2(3.5, 3.5)), false);
 		assert_eq!(r.contains(vec2(2.5, 4.5)), false);
 	}
+
+	#[test]
+	fn rect_intersects() {
+		let r = Rect::from_center(vec2(0., 0.), vec2(2., 2.));
+		let at = |x: f32, y: f32| Rect::from_center(vec2(x, y), vec2(1., 1.));
+		assert!(r.intersects(at(0., 0.)));
+		assert!(r.intersects(at(1.2, -1.2)));
+		assert!(at(1.2, -1.2).intersects(r));
+		assert!(!r.intersects(at(0., 1.6)));
+		assert!(!r.intersects(at(0., -1.6)));
+		assert!(!r.intersects(at(1.6, 0.)));
+		assert!(!r.intersects(at(-1.6, 0.)));
+	}
+
+	#[test]
+	fn rect_dimensions() {
+		// top is the smaller value, as with `contains`
+		let r = Rect {
+			left: 1.,
+			right: 3.,
+			top: 2.,
+			bottom: 6.,
+		};
+		assert_eq!(r.center(), vec2(2., 4.));
+		assert_eq!(r.size(), vec2(2., 4.));
+		assert_eq!(r.area(), 8.);
+	}
+
+	#[test]
+	fn rect_from_center() {
+		let r = Rect::from_center(vec2(2., 4.), vec2(2., 4.));
+		assert_eq!((r.left, r.right, r.top, r.bottom), (1., 3., 2., 6.));
+		assert_eq!(r.center(), vec2(2., 4.));
+		assert!(r.contains(vec2(1.5, 5.5)));
+	}
 }
@@ -217,6 +217,7 @@ fn mesh_from_vertex_data(
 			pos: *pos,
 			normal: *normal,
 			uv: *uv,
+			color: Vertex::WHITE,
 		})
 	}
 	Mesh { vertices, indices }
@@ -1,7 +1,9 @@
 #![allow(dead_code)]
 
+use super::Children;
+use bevy_ecs_wasm::prelude::{Entity, Query};
 use glam::{Mat3, Mat4, Quat, Vec3};
-use std::ops::Mul;
+use std::{collections::HashSet, ops::Mul};
 
 // adapted from bevy_transform/src/components/global_transform.rs
 #[derive(Debug, Clone)]
@@ -163,9 +165,18 @@ impl GlobalTransform {
 		self.scale *= scale;
 	}
 
+	/// Turns to face `target`. When the look direction is parallel to `up`, so there's no side
+	/// to roll towards, world forward (or world up, when looking along Z) is used as up instead.
 	#[inline]
 	pub fn look_at(&mut self, target: Vec3, up: Vec3) {
 		let forward = Vec3::normalize(self.translation - target);
+		let up = if up.cross(forward).length_squared() > 1e-6 {
+			up
+		} else if forward.z.abs() < 0.9 {
+			-Vec3::Z
+		} else {
+			Vec3::Y
+		};
 		let right = up.cross(forward).normalize();
 		let up = forward.cross(right);
 		self.rotation = Quat::from_mat3(&Mat3::from_cols(right, up, forward));
@@ -195,3 +206,200 @@ impl Mul<Vec3> for GlobalTransform {
 		self.mul_vec3(value)
 	}
 }
+
+/// Local transform, relative to the parent entity (the one listing this entity in its
+/// [`Children`]). The [`GlobalTransform`] of entities with a `Transform` is computed from it by
+/// [`propagate_transforms`]; entities without a parent use it as their global transform.
+#[derive(Debug, Clone)]
+pub struct Transform {
+	pub translation: Vec3,
+	pub rotation: Quat,
+	pub scale: Vec3,
+}
+
+impl Transform {
+	#[inline]
+	pub const fn identity() -> Self {
+		Transform {
+			translation: Vec3::ZERO,
+			rotation: Quat::IDENTITY,
+			scale: Vec3::ONE,
+		}
+	}
+
+	#[inline]
+	pub fn from_translation(translation: Vec3) -> Self {
+		Transform {
+			translation,
+			..Self::identity()
+		}
+	}
+
+	#[inline]
+	pub fn from_matrix(matrix: Mat4) -> Self {
+		let (scale, rotation, translation) = matrix.to_scale_rotation_translation();
+		Transform {
+			translation,
+			rotation,
+			scale,
+		}
+	}
+}
+
+impl Default for Transform {
+	fn default() -> Self {
+		Self::identity()
+	}
+}
+
+impl From<Transform> for GlobalTransform {
+	fn from(t: Transform) -> Self {
+		GlobalTransform {
+			translation: t.translation,
+			rotation: t.rotation,
+			scale: t.scale,
+		}
+	}
+}
+
+/// Composes local [`Transform`]s with the [`GlobalTransform`] of their parents, top-down through
+/// [`Children`]. Entities without a `Transform` keep their `GlobalTransform` as is.
+pub fn propagate_transforms(
+	mut q_global: Query<&mut GlobalTransform>,
+	q_local: Query<(Entity, &Transform)>,
+	q_children: Query<(Entity, &Children)>,
+) {
+	let child_entities = q_children
+		.iter()
+		.flat_map(|(_, children)| children.0.iter().copied())
+		.collect::<HashSet<_>>();
+	let roots = q_children
+		.iter()
+		.map(|(e, _)| e)
+		.chain(q_local.iter().map(|(e, _)| e))
+		.filter(|e| !child_entities.contains(e))
+		.collect::<HashSet<_>>();
+	for root in roots.into_iter() {
+		propagate_recursive(
+			root,
+			&GlobalTransform::identity(),
+			&mut q_global,
+			&q_local,
+			&q_children,
+		);
+	}
+}
+
+fn propagate_recursive(
+	entity: Entity,
+	parent: &GlobalTransform,
+	q_global: &mut Query<&mut GlobalTransform>,
+	q_local: &Query<(Entity, &Transform)>,
+	q_children: &Query<(Entity, &Children)>,
+) {
+	let global = if let Ok((_, local)) = q_local.get(entity) {
+		let global = parent.mul_transform(local.clone().into());
+		if let Ok(mut current) = q_global.get_mut(entity) {
+			// only write actual changes, to keep change detection meaningful
+			if current.translation != global.translation
+				|| current.rotation != global.rotation
+				|| current.scale != global.scale
+			{
+				*current = global.clone();
+			}
+		}
+		global
+	} else {
+		q_global.get_mut(entity).map(|g| g.clone()).unwrap_or_else(|_| parent.clone())
+	};
+	if let Ok((_, children)) = q_children.get(entity) {
+		for child in children.0.iter() {
+			propagate_recursive(*child, &global, q_global, q_local, q_children);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::prelude::*;
+	use glam::vec3;
+	use std::f32::consts::PI;
+
+	#[test]
+	fn compose_multi_level_hierarchy() {
+		let mut app = App::new()
+			.add_system_to_stage(CoreStage::PostUpdate, propagate_transforms.system())
+			.build();
+		let world = &mut app.world;
+		let grandchild = world
+			.spawn()
+			.insert_bundle((
+				Transform::from_translation(vec3(0., 0., 3.)),
+				GlobalTransform::identity(),
+			))
+			.id();
+		let child = world
+			.spawn()
+			.insert_bundle((
+				Transform::from_translation(vec3(0., 2., 0.)),
+				GlobalTransform::identity(),
+				Children(vec![grandchild]),
+			))
+			.id();
+		let root = world
+			.spawn()
+			.insert_bundle((
+				Transform::from_translation(vec3(1., 0., 0.)),
+				GlobalTransform::identity(),
+				Children(vec![child]),
+			))
+			.id();
+		let orphan = world
+			.spawn()
+			.insert_bundle((
+				Transform::from_translation(vec3(5., 0., 0.)),
+				GlobalTransform::identity(),
+			))
+			.id();
+
+		app.dispatch_update();
+
+		let translation = |e: Entity| app.world.get::<GlobalTransform>(e).unwrap().translation;
+		assert_eq!(translation(root), vec3(1., 0., 0.));
+		assert_eq!(translation(child), vec3(1., 2., 0.));
+		assert_eq!(translation(grandchild), vec3(1., 2., 3.));
+		assert_eq!(translation(orphan), vec3(5., 0., 0.));
+	}
+
+	#[test]
+	fn direction_vectors() {
+		let identity = GlobalTransform::identity();
+		assert_eq!(identity.forward(), -Vec3::Z);
+		assert_eq!(identity.right(), Vec3::X);
+		assert_eq!(identity.up(), Vec3::Y);
+		// cameras look along their forward vector
+		let camera = CameraBundle::default().transform;
+		assert!(camera.forward().abs_diff_eq(-Vec3::Z, 1e-6));
+		assert!(camera.up().abs_diff_eq(Vec3::Y, 1e-6));
+
+		let turned_left = GlobalTransform::from_rotation(Quat::from_rotation_y(PI / 2.));
+		assert!(turned_left.forward().abs_diff_eq(-Vec3::X, 1e-6));
+		assert!(turned_left.right().abs_diff_eq(-Vec3::Z, 1e-6));
+		assert!(turned_left.up().abs_diff_eq(Vec3::Y, 1e-6));
+	}
+
+	#[test]
+	fn look_along_up_vector() {
+		for target in vec![Vec3::Y, -Vec3::Y, Vec3::Z] {
+			let up = if target == Vec3::Z { Vec3::Z } else { Vec3::Y };
+			let transform = GlobalTransform::identity().looking_at(target, up);
+			assert!(transform.rotation.is_finite());
+			assert!(transform.rotation.is_normalized());
+			assert!(transform.forward().abs_diff_eq(target, 1e-5));
+		}
+		// looking straight up from the ground keeps world forward at the top of the view
+		let looking_up = GlobalTransform::identity().looking_at(Vec3::Y, Vec3::Y);
+		assert!(looking_up.up().abs_diff_eq(-Vec3::Z, 1e-5));
+	}
+}